@@ -1,16 +1,22 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::heuristic::*;
 use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::search_space::*;
 use crate::algorithm::strategy::*;
-use crate::world::grid::*;
-use crate::world::types::*;
-use macroquad::prelude::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::CountingGeometry;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
-use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+use std::time::Instant;
 
+/// Queue entry of a label, ordered by `f` and then by `h`.
 #[derive(Debug, Clone, PartialEq)]
 struct Node {
-    pos: Vec2,
-    pre: Option<Vec2>,
+    label: usize,
     g: f32,
     h: f32,
 }
@@ -21,11 +27,7 @@ impl Node {
 }
 impl Ord for Node {
     fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .f()
-            .partial_cmp(&self.f())
-            .unwrap_or(Ordering::Equal)
-            .then_with(|| self.h.partial_cmp(&other.h).unwrap_or(Ordering::Equal))
+        other.f().total_cmp(&self.f()).then_with(|| self.h.total_cmp(&other.h))
     }
 }
 impl PartialOrd for Node {
@@ -35,171 +37,290 @@ impl PartialOrd for Node {
 }
 impl Eq for Node {}
 
-impl Hash for Node {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.pos.x.to_bits().hash(state);
-        self.pos.y.to_bits().hash(state);
-    }
+/// One way of reaching a search node, with its cost and the hazard accumulated on the way.
+struct Label<N> {
+    node: N,
+    g: f32,
+    hazard: f64,
+    parent: Option<usize>,
+    /// Set once another label reaches the node at most as costly and at most as hazardous.
+    dominated: bool,
 }
 
-pub struct AStarStrategy {}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ProbeDirection {
-    Right,
-    UpRight,
-    Up,
-    UpLeft,
-    Left,
-    DownLeft,
-    Down,
-    DownRight,
+/// Grid-free A* over a `ProbeSpace`, other spaces are searched through `AStarStrategy::search`.
+///
+/// The search does not stop at the first node that sees the goal but keeps going until no queued node can beat
+/// the cheapest goal hop found, which costs extra expansions on open maps in exchange for the shortest path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AStarStrategy {
+    /// Probe length, diagonal probes reach `sqrt(2)` times further. Defaults to 25.
+    pub step_size: f32,
+    /// Estimate of the remaining distance, estimates above the Euclidean one may give longer paths. Euclidean.
+    pub heuristic: HeuristicKind,
 }
-impl ProbeDirection {
-    pub fn iter() -> std::slice::Iter<'static, Self> {
-        static DIRECTIONS: [ProbeDirection; 8] = [
-            ProbeDirection::Right,
-            ProbeDirection::UpRight,
-            ProbeDirection::Up,
-            ProbeDirection::UpLeft,
-            ProbeDirection::Left,
-            ProbeDirection::DownLeft,
-            ProbeDirection::Down,
-            ProbeDirection::DownRight,
-        ];
-        DIRECTIONS.iter()
+
+impl Default for AStarStrategy {
+    fn default() -> Self {
+        Self {
+            step_size: 25.0,
+            heuristic: HeuristicKind::Euclidean,
+        }
     }
 }
+
 impl AStarStrategy {
-    const STEP_SIZE: f32 = 25.0;
+    /// A* over any search space, the path ends with the cheapest direct hop to the goal among the expanded nodes.
+    /// The search stops once no queued node can undercut it, which is optimal for admissible heuristics.
+    ///
+    /// Under a hazard budget a costlier but safer arrival at a node may be the only one that still reaches the
+    /// goal, so every node keeps all arrivals no other one beats on both cost and hazard. Without a risk config
+    /// the hazard is always zero and this is plain A*.
+    pub fn search<S: SearchSpace>(
+        &self,
+        space: &S,
+        problem: &Problem,
+        context: &PlanContext,
+    ) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
 
-    fn heuristic(a: Vec2, b: Vec2) -> f32 {
-        a.distance(b)
-    }
+        let world = CountingGeometry::new(problem.world());
+        let mut stats = SearchStats::default();
+        let start = problem.start();
+        let goal = problem.goal();
+        let hazard_budget = problem.hazard_budget();
 
-    fn has_line_of_sight(from: Vec2, to: Vec2, grid_map: &GridMap) -> bool {
-        let direction = (to - from).normalize_or_zero();
-        let distance = from.distance(to);
-        if distance == 0.0 {
-            return true;
-        }
+        // The problem is valid, so a start the space cannot place has no way into it
+        let start_node = space.locate(problem, start).ok_or(PlanError::Unreachable)?;
+        let (start_cost, start_hazard) =
+            link_hop(problem, &world, start, space.position(&start_node)).ok_or(PlanError::Unreachable)?;
 
-        let ray = Ray {
-            root: from,
-            dir: direction,
-        };
+        let mut queue = BinaryHeap::new();
+        let mut labels: Vec<Label<S::Node>> = Vec::new();
+        // Labels per node that no other label at the node beats on both cost and hazard
+        let mut frontiers: HashMap<S::Node, Vec<usize>> = HashMap::new();
+        let mut successors = Vec::new();
 
-        grid_map.raycast(ray).map_or(true, |hit| hit.dist >= distance)
-    }
+        queue.push(Node {
+            label: 0,
+            g: start_cost,
+            h: space.heuristic(problem, &self.heuristic, &start_node, goal),
+        });
+        frontiers.insert(start_node.clone(), vec![0]);
+        labels.push(Label {
+            node: start_node,
+            g: start_cost,
+            hazard: start_hazard,
+            parent: None,
+            dominated: false,
+        });
 
-    fn get_new_pos(root: Vec2, direction: ProbeDirection, grid_map: &GridMap) -> Vec2 {
-        let offset = match direction {
-            ProbeDirection::Right => Vec2::X,               // (1, 0)
-            ProbeDirection::UpRight => Vec2::X + Vec2::Y,   // (1, 1)
-            ProbeDirection::Up => Vec2::Y,                  // (0, 1)
-            ProbeDirection::UpLeft => -Vec2::X + Vec2::Y,   // (-1, 1)
-            ProbeDirection::Left => -Vec2::X,               // (-1, 0)
-            ProbeDirection::DownLeft => -Vec2::X - Vec2::Y, // (-1, -1)
-            ProbeDirection::Down => -Vec2::Y,               // (0, -1)
-            ProbeDirection::DownRight => Vec2::X - Vec2::Y, // (1, -1)
-        };
+        let mut goal_link: Option<(usize, f32)> = None;
 
-        let ray = Ray { root, dir: offset };
+        while let Some(cur) = queue.pop() {
+            if labels[cur.label].dominated {
+                continue;
+            }
+            // No queued node can beat the cheapest goal hop found so far
+            if let Some((_, goal_cost)) = &goal_link
+                && cur.f() >= *goal_cost
+            {
+                break;
+            }
+
+            // A* holds no complete path before it reaches the goal, any stop is an error
+            context.check(stats.nodes_expanded)?;
+            stats.nodes_expanded += 1;
+            let cur_node = labels[cur.label].node.clone();
+            let cur_hazard = labels[cur.label].hazard;
+            let cur_pos = space.position(&cur_node);
+            context.emit(|observer| observer.node_expanded(cur_pos));
+            stats.peak_memory_bytes = stats.peak_memory_bytes.max(
+                queue.len() * size_of::<Node>()
+                    + labels.len() * size_of::<Label<S::Node>>()
+                    + frontiers.len() * size_of::<(S::Node, Vec<usize>)>(),
+            );
+
+            if let Some(hop_cost) = space.connect_goal(problem, &world, &cur_node, goal)
+                && cur_hazard + problem.segment_hazard(cur_pos, goal) <= hazard_budget
+                && goal_link.as_ref().is_none_or(|(_, goal_cost)| cur.g + hop_cost < *goal_cost)
+            {
+                goal_link = Some((cur.label, cur.g + hop_cost));
+                // The hop meets the estimate, every queued node already costs at least as much
+                if hop_cost <= cur.h {
+                    break;
+                }
+            }
+
+            successors.clear();
+            space.successors(problem, &world, &cur_node, &mut successors);
+            for (next, cost) in successors.drain(..) {
+                let next_pos = space.position(&next);
+                let new_hazard = cur_hazard + problem.segment_hazard(cur_pos, next_pos);
+                if new_hazard > hazard_budget {
+                    continue;
+                }
 
-        if let Some(hit) = grid_map.raycast(ray) {
-            root + offset * hit.dist.min(AStarStrategy::STEP_SIZE)
-        } else {
-            root + offset * AStarStrategy::STEP_SIZE
+                let new_g = cur.g + cost;
+                let frontier = frontiers.entry(next.clone()).or_default();
+                if frontier
+                    .iter()
+                    .any(|&index| labels[index].g <= new_g && labels[index].hazard <= new_hazard)
+                {
+                    continue;
+                }
+                frontier.retain(|&index| {
+                    let beaten = new_g <= labels[index].g && new_hazard <= labels[index].hazard;
+                    labels[index].dominated |= beaten;
+                    !beaten
+                });
+
+                let label = labels.len();
+                frontier.push(label);
+                queue.push(Node {
+                    label,
+                    g: new_g,
+                    h: space.heuristic(problem, &self.heuristic, &next, goal),
+                });
+                labels.push(Label {
+                    node: next,
+                    g: new_g,
+                    hazard: new_hazard,
+                    parent: Some(cur.label),
+                    dominated: false,
+                });
+            }
         }
+
+        let (last, cost) = goal_link.ok_or(PlanError::Unreachable)?;
+        let mut path = vec![goal];
+        let mut current = Some(last);
+        while let Some(index) = current {
+            path.push(space.position(&labels[index].node));
+            current = labels[index].parent;
+        }
+        if path.last() != Some(&start) {
+            path.push(start);
+        }
+        path.reverse();
+
+        context.emit(|observer| observer.new_best(&path, cost as f64));
+        stats.raycasts = world.raycasts();
+        stats.segment_checks = world.segment_checks();
+        let result = certify(problem, PlanResult::new(problem, path, started.elapsed(), stats))?;
+        context.report_improvement(|| result.clone());
+        Ok(result)
     }
 }
 
 impl Strategy for AStarStrategy {
-    fn path_finding(&self, problem: &Problem) -> Option<Vec<Vec2>> {
-        let grid_map = problem.grid_map();
-        let start = problem.start();
-        let goal = problem.goal();
-
-        let mut queue = BinaryHeap::new();
-        let mut g_costs: HashMap<(u32, u32), f32> = HashMap::new();
-        let mut predecessors: HashMap<(u32, u32), Vec2> = HashMap::new();
-
-        let start_node = Node {
-            pos: start,
-            pre: None,
-            g: 0.0,
-            h: AStarStrategy::heuristic(start, goal),
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let space = ProbeSpace {
+            step_size: self.step_size,
         };
-        queue.push(start_node);
+        self.search(&space, problem, context)
+    }
+}
 
-        let start_key = (start.x.to_bits(), start.y.to_bits());
-        g_costs.insert(start_key, 0.0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+    use crate::world::grid::{GridMap, GridNodeValue};
+    use crate::world::occupancy::OccupancyConfig;
+    use glam::Vec2;
+    use std::sync::Arc;
 
-        let mut goal_node: Option<Node> = None;
+    /// One lattice node per cell of a 300 x 150 world.
+    const LATTICE: LatticeSpace = LatticeSpace {
+        node_dist: 10.0,
+        diagonal: false,
+    };
 
-        while let Some(cur) = queue.pop() {
-            let cur_key = (cur.pos.x.to_bits(), cur.pos.y.to_bits());
+    /// A wall at x 200..210 with a one cell door at y 70..80. An avoidable risky strip lies straight ahead of
+    /// the start and the door itself is risky, together they exceed the hazard budget.
+    fn risky_door_problem() -> Problem {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (30, 15),
+            cell_size: 10.0,
+        });
+        for y in (0..15).filter(|&y| y != 7) {
+            grid_map.grid_mut().set(20, y, GridNodeValue::Obstacle);
+        }
+        let occupancy = grid_map.enable_occupancy(OccupancyConfig {
+            prior: 0.0,
+            ..Default::default()
+        });
+        for y in 3..12 {
+            occupancy.set_probability(10, y, 0.2);
+            occupancy.set_probability(11, y, 0.2);
+        }
+        for x in 19..22 {
+            occupancy.set_probability(x, 7, 0.2);
+        }
 
-            if let Some(&best_g) = g_costs.get(&cur_key) {
-                if cur.g > best_g {
-                    continue;
-                }
-            }
+        // Strip and door cost five cells at p = 0.2, the budget allows four
+        let cell_hazard = -(0.8f64).ln();
+        let bound = 1.0 - (-4.0 * cell_hazard).exp();
+        Problem::new(Arc::new(grid_map), Vec2::new(25.0, 75.0), Vec2::new(275.0, 75.0)).with_risk(RiskConfig {
+            weight: 0.0,
+            bound: bound as f32,
+        })
+    }
 
-            if AStarStrategy::has_line_of_sight(cur.pos, goal, grid_map) {
-                let goal_key = (goal.x.to_bits(), goal.y.to_bits());
-                predecessors.insert(goal_key, cur.pos);
-                goal_node = Some(Node {
-                    pos: goal,
-                    pre: Some(cur.pos),
-                    g: cur.g + goal.distance(cur.pos),
-                    h: 0.0,
-                });
-                break;
-            }
+    #[test]
+    fn detours_around_risk_when_the_shortest_route_exceeds_the_budget() {
+        let problem = risky_door_problem();
+        let result = AStarStrategy::default()
+            .search(&LATTICE, &problem, &PlanContext::new())
+            .expect("the detour stays within the budget");
 
-            for dir in ProbeDirection::iter() {
-                let new_pos = AStarStrategy::get_new_pos(cur.pos, *dir, grid_map);
-                let new_dist = new_pos.distance(cur.pos);
-                let new_g = cur.g + new_dist;
-                let new_key = (new_pos.x.to_bits(), new_pos.y.to_bits());
-
-                let should_process = g_costs.get(&new_key).map_or(true, |&existing_g| new_g < existing_g);
-
-                if should_process {
-                    let new_node = Node {
-                        pos: new_pos,
-                        pre: Some(cur.pos),
-                        g: new_g,
-                        h: AStarStrategy::heuristic(new_pos, goal),
-                    };
-
-                    queue.push(new_node);
-                    g_costs.insert(new_key, new_g);
-                    predecessors.insert(new_key, cur.pos);
-                }
-            }
+        let hazard: f64 = result.path.windows(2).map(|seg| problem.segment_hazard(seg[0], seg[1])).sum();
+        assert!(hazard <= problem.hazard_budget());
+        assert!(PathValidator::default().violations(&problem, &result.path).is_empty());
+    }
+
+    /// A 300 x 300 world whose obstacles hide the four lattice points around the start (45, 45) from it.
+    fn hidden_lattice_problem() -> Problem {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (30, 30),
+            cell_size: 10.0,
+        });
+        for (x, y) in [(3, 3), (5, 3), (3, 5), (5, 5)] {
+            grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
         }
+        Problem::new(Arc::new(grid_map), Vec2::new(45.0, 45.0), Vec2::new(255.0, 255.0))
+    }
 
-        if let Some(goal) = goal_node {
-            let mut path = Vec::new();
-            let mut current = goal.pos;
-            path.push(current);
-
-            while current != start {
-                let cur_key = (current.x.to_bits(), current.y.to_bits());
-                if let Some(&prev) = predecessors.get(&cur_key) {
-                    path.push(prev);
-                    current = prev;
-                } else {
-                    return None;
-                }
-            }
+    #[test]
+    fn links_the_start_to_a_reachable_lattice_point() {
+        let problem = hidden_lattice_problem();
+        let lattice = LatticeSpace::new(50.0);
+        let result = AStarStrategy::default().search(&lattice, &problem, &PlanContext::new()).unwrap();
+        assert!(PathValidator::default().violations(&problem, &result.path).is_empty());
 
-            path.reverse();
-            Some(path)
-        } else {
-            None
+        // Walled in on all sides, no lattice point can be reached
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (30, 30),
+            cell_size: 10.0,
+        });
+        for (x, y) in [(3, 3), (4, 3), (5, 3), (3, 4), (5, 4), (3, 5), (4, 5), (5, 5)] {
+            grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
         }
+        let enclosed = Problem::new(Arc::new(grid_map), Vec2::new(45.0, 45.0), Vec2::new(255.0, 255.0));
+        assert_eq!(lattice.locate(&enclosed, enclosed.start()), None);
+        assert!(matches!(
+            AStarStrategy::default().search(&lattice, &enclosed, &PlanContext::new()),
+            Err(PlanError::Unreachable)
+        ));
+    }
+
+    #[test]
+    fn takes_the_straight_route_without_risk() {
+        let mut problem = risky_door_problem();
+        problem.risk = None;
+        let result = AStarStrategy::default().search(&LATTICE, &problem, &PlanContext::new()).unwrap();
+        assert!((result.length - 250.0).abs() < 1e-3);
     }
 }
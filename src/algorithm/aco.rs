@@ -1,123 +1,161 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::heuristic::*;
 use crate::algorithm::problem::Problem;
+use crate::algorithm::result::*;
+use crate::algorithm::search_space::*;
 use crate::algorithm::strategy::*;
-use crate::game::temporary_dot_renderer::draw_temporary_dot;
-use crate::world::{grid::*, types::*};
-use macroquad::prelude::*;
+use crate::algorithm::validator::*;
+use crate::generator::seeded_rng;
+use crate::world::geometry::CountingGeometry;
+use glam::Vec2;
+use quad_rand::RandGenerator;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
-
-#[derive(Debug, Clone, Eq)]
-pub struct Node {
-    pos: (i32, i32),
-}
-impl Node {
-    fn new(x: i32, y: i32) -> Self {
-        Node { pos: (x, y) }
-    }
-}
-impl Hash for Node {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.pos.hash(state);
-    }
-}
-impl PartialEq for Node {
-    fn eq(&self, other: &Self) -> bool {
-        self.pos == other.pos
-    }
-}
-
-#[derive(Debug, Clone, Eq)]
-struct Path {
-    from: Node,
-    to: Node,
-}
-impl Path {
-    fn new(from: Node, to: Node) -> Self {
-        Path { from, to }
-    }
-}
-impl Hash for Path {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.from.hash(state);
-        self.to.hash(state);
-    }
-}
-impl PartialEq for Path {
-    fn eq(&self, other: &Self) -> bool {
-        self.from == other.from && self.to == other.to
-    }
-}
+use std::mem::size_of;
+use std::time::Instant;
 
+/// Ant colony search over a lattice of nodes `node_dist` apart, missing fields deserialize to the defaults.
+/// Other spaces are searched through `AcoStrategy::search`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AcoStrategy {
+    /// Lattice spacing, 50.
     pub node_dist: f32,
 
+    /// Weight of the pheromone in an ant's choice, 1.
     pub alpha: f64,
+    /// Weight of the closeness to the goal in an ant's choice, 5.
     pub beta: f64,
+    /// Distance estimate the closeness is the inverse of, Euclidean.
+    pub heuristic: HeuristicKind,
+    /// Share of pheromone lost on every edge each time an ant deposits, 0.2.
     pub evaporation: f64,
+    /// Pheromone an ant spreads over its route, divided by the route cost, 2000.
     pub deposit_constant: f64,
 
+    /// Pheromone of edges no ant has used yet, 0.05.
     pub init_pheromone: f64,
+    /// Ants dispatched in total, 1000.
     pub min_ant_count: u32,
+    /// Steps an ant may take before it gives up, 500.
     pub max_ant_try: u32,
+
+    /// Seeds the ants' choices, the same seed always gives the same path. 0.
+    pub seed: u64,
 }
 
-impl Strategy for AcoStrategy {
-    fn path_finding(&self, problem: &Problem) -> Option<Vec<Vec2>> {
-        let grid_map = problem.grid_map();
+impl Default for AcoStrategy {
+    fn default() -> Self {
+        Self {
+            node_dist: 50.0,
+            alpha: 1.0,
+            beta: 5.0,
+            heuristic: HeuristicKind::Euclidean,
+            evaporation: 0.2,
+            deposit_constant: 2000.0,
+            init_pheromone: 0.05,
+            min_ant_count: 1000,
+            max_ant_try: 500,
+            seed: 0,
+        }
+    }
+}
+
+impl AcoStrategy {
+    /// Ant colony search over any search space, an ant succeeds once it steps onto the goal's node.
+    pub fn search<S: SearchSpace>(
+        &self,
+        space: &S,
+        problem: &Problem,
+        context: &PlanContext,
+    ) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+
+        let world = CountingGeometry::new(problem.world());
+        let mut stats = SearchStats::default();
+        let seed = context.seed().unwrap_or(self.seed);
+        let rng = seeded_rng(seed);
         let start = problem.start();
         let goal = problem.goal();
-
-        let start_node = self.world_to_node_pos(start).unwrap();
-        let goal_node = self.world_to_node_pos(goal).unwrap();
-
-        let mut path_pheromones: HashMap<Path, f64> = HashMap::new();
-
-        let mut best_aco_route: Option<Vec<Node>> = None;
-
-        for _ in 0..self.min_ant_count {
-            let mut route: Vec<Node> = Vec::new();
-            let mut visited: HashSet<Node> = HashSet::new();
+        let hazard_budget = problem.hazard_budget();
+
+        // The problem is valid, so a start or goal the space cannot place has no way into it
+        let start_node = space.locate(problem, start).ok_or(PlanError::Unreachable)?;
+        let goal_node = space.locate(problem, goal).ok_or(PlanError::Unreachable)?;
+        // Every route is linked to the exact start and goal, those hops count towards its cost and hazard
+        let (start_cost, start_hazard) =
+            link_hop(problem, &world, start, space.position(&start_node)).ok_or(PlanError::Unreachable)?;
+        let (goal_cost, goal_hazard) =
+            link_hop(problem, &world, space.position(&goal_node), goal).ok_or(PlanError::Unreachable)?;
+
+        let mut path_pheromones: HashMap<(S::Node, S::Node), f64> = HashMap::new();
+
+        let mut best_aco_route: Option<(Vec<S::Node>, f64)> = None;
+        let mut stopped = None;
+        let mut successors = Vec::new();
+
+        for ant in 0..self.min_ant_count as usize {
+            if let Some(stop) = context.stop_reason(stats.ants_dispatched) {
+                if stop == PlanError::Cancelled || best_aco_route.is_none() {
+                    return Err(stop);
+                }
+                stopped = Some(stop);
+                break;
+            }
+            stats.ants_dispatched += 1;
+            let mut route: Vec<S::Node> = Vec::new();
+            let mut visited: HashSet<S::Node> = HashSet::new();
 
             let mut cur_node = start_node.clone();
             route.push(cur_node.clone());
             visited.insert(cur_node.clone());
+            let mut route_hazard = start_hazard;
+            let mut route_cost = start_cost as f64;
             let mut try_count: i32 = self.max_ant_try as i32;
             let path_found = loop {
-                let mut node_desires: Vec<(Node, f64)> = Vec::new();
-                let mut total_desire = 0.0;
-                for next_node in self.next_node_list(cur_node.clone()) {
-                    let cur_pos = self.node_to_world_pos(cur_node.clone());
-                    let next_pos = self.node_to_world_pos(next_node.clone());
+                if context.is_cancelled() {
+                    return Err(PlanError::Cancelled);
+                }
+                let cur_pos = space.position(&cur_node);
+                successors.clear();
+                space.successors(problem, &world, &cur_node, &mut successors);
 
-                    if !self.has_sight(cur_pos.clone(), next_pos.clone(), &grid_map) {
+                let mut node_desires: Vec<(S::Node, f32, f64)> = Vec::new();
+                let mut total_desire = 0.0;
+                for (next_node, cost) in successors.drain(..) {
+                    let next_pos = space.position(&next_node);
+                    if route_hazard + problem.segment_hazard(cur_pos, next_pos) > hazard_budget {
                         continue;
                     }
 
-                    let mut desire =
-                        self.path_desire(Path::new(cur_node.clone(), next_node.clone()), &path_pheromones, goal.clone());
+                    let mut desire = self.path_desire(space, problem, &cur_node, &next_node, &path_pheromones, goal);
 
                     if visited.contains(&next_node) {
                         desire *= 0.001;
                     }
 
-                    node_desires.push((next_node, desire));
+                    node_desires.push((next_node, cost, desire));
                     total_desire += desire;
                 }
                 if node_desires.is_empty() {
                     break false;
                 }
-                let next_node = self.get_next_node(&node_desires, total_desire);
+                let (next_node, cost) = self.get_next_node(&rng, &node_desires, total_desire);
+                let next_pos = space.position(&next_node);
+                route_hazard += problem.segment_hazard(cur_pos, next_pos);
+                route_cost += cost as f64;
+                context.emit(|observer| observer.ant_moved(ant, cur_pos, next_pos));
                 cur_node = next_node;
 
                 route.push(cur_node.clone());
                 visited.insert(cur_node.clone());
 
-                // Debug
-                draw_temporary_dot(self.node_to_world_pos(cur_node.clone()), WHITE, 10.0, 0.1);
-                //
-
                 if cur_node == goal_node {
-                    break true;
+                    route_hazard += goal_hazard;
+                    route_cost += goal_cost as f64;
+                    break route_hazard <= hazard_budget;
                 }
 
                 try_count -= 1;
@@ -126,104 +164,210 @@ impl Strategy for AcoStrategy {
                 }
             };
 
+            stats.peak_memory_bytes = stats.peak_memory_bytes.max(
+                path_pheromones.len() * size_of::<((S::Node, S::Node), f64)>()
+                    + (route.len() + visited.len()) * size_of::<S::Node>(),
+            );
+
+            context.emit(|observer| observer.ant_finished(ant, &route_positions(space, &route), path_found));
+
+            let mut improved = false;
             if path_found {
-                self.update_pheromone(&route, route.len() as f64 * self.node_dist as f64, &mut path_pheromones);
+                self.update_pheromone(space, &route, route_cost, &mut path_pheromones, context);
 
-                if best_aco_route.is_none() || best_aco_route.as_ref().unwrap().len() > route.len() {
-                    best_aco_route = Some(route);
+                if best_aco_route.as_ref().is_none_or(|(_, best_cost)| *best_cost > route_cost) {
+                    context.emit(|observer| observer.new_best(&linked_path(space, start, &route, goal), route_cost));
+                    best_aco_route = Some((route, route_cost));
+                    improved = true;
                 }
             }
-        }
 
-        if let Some(best_route) = best_aco_route {
-            Some(best_route.iter().map(|x| self.node_to_world_pos(x.clone())).collect())
-        } else {
-            None
+            stats.iterations += 1;
+            stats
+                .best_per_iteration
+                .push(best_aco_route.as_ref().map_or(f64::INFINITY, |(_, cost)| *cost));
+
+            if improved && let Some((best_route, _)) = &best_aco_route {
+                stats.raycasts = world.raycasts();
+                stats.segment_checks = world.segment_checks();
+                context.report_improvement(|| {
+                    PlanResult::new(problem, linked_path(space, start, best_route, goal), started.elapsed(), stats.clone())
+                        .with_seed(seed)
+                });
+            }
         }
-    }
-}
 
-impl AcoStrategy {
-    fn world_to_node_pos(&self, wpos: Vec2) -> Option<Node> {
-        if wpos.is_nan() {
-            None
-        } else {
-            Some(Node::new(
-                ((wpos.x - self.node_dist / 2.0) / self.node_dist).round() as i32,
-                ((wpos.y - self.node_dist / 2.0) / self.node_dist).round() as i32,
-            ))
-        }
-    }
-    fn node_to_world_pos(&self, npos: Node) -> Vec2 {
-        Vec2::new(
-            npos.pos.0 as f32 * self.node_dist + self.node_dist / 2.0,
-            npos.pos.1 as f32 * self.node_dist + self.node_dist / 2.0,
+        // Ants only give up, they cannot prove the goal unreachable
+        let (best_route, _) = best_aco_route.ok_or(PlanError::IterationBudgetExhausted)?;
+        let path = linked_path(space, start, &best_route, goal);
+        stats.raycasts = world.raycasts();
+        stats.segment_checks = world.segment_checks();
+        certify(
+            problem,
+            PlanResult::new(problem, path, started.elapsed(), stats)
+                .with_seed(seed)
+                .with_stopped(stopped),
         )
     }
+}
 
-    fn next_node_list(&self, npos: Node) -> Vec<Node> {
-        let mut v = Vec::new();
-        v.push(Node::new(npos.pos.0 + 1, npos.pos.1 + 0));
-        // v.push(Node::new(npos.pos.0 + 1, npos.pos.1 - 1));
-        v.push(Node::new(npos.pos.0 + 0, npos.pos.1 - 1));
-        // v.push(Node::new(npos.pos.0 - 1, npos.pos.1 - 1));
-        v.push(Node::new(npos.pos.0 - 1, npos.pos.1 + 0));
-        // v.push(Node::new(npos.pos.0 - 1, npos.pos.1 + 1));
-        v.push(Node::new(npos.pos.0 + 0, npos.pos.1 + 1));
-        // v.push(Node::new(npos.pos.0 + 1, npos.pos.1 + 1));
-        v
+impl Strategy for AcoStrategy {
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        self.search(&LatticeSpace::new(self.node_dist), problem, context)
     }
+}
 
-    fn has_sight(&self, from: Vec2, to: Vec2, grid_map: &GridMap) -> bool {
-        let direction = (to - from).normalize_or_zero();
-        let distance = from.distance(to);
-        if distance == 0.0 {
-            return true;
-        }
-
-        let ray = Ray {
-            root: from,
-            dir: direction,
-        };
+fn route_positions<S: SearchSpace>(space: &S, route: &[S::Node]) -> Vec<Vec2> {
+    route.iter().map(|node| space.position(node)).collect()
+}
 
-        grid_map.raycast(ray).map_or(true, |hit| hit.dist >= distance)
+/// The route's positions with `start` and `goal` added wherever they differ from its first and last node.
+fn linked_path<S: SearchSpace>(space: &S, start: Vec2, route: &[S::Node], goal: Vec2) -> Vec<Vec2> {
+    let mut path = route_positions(space, route);
+    if path.first() != Some(&start) {
+        path.insert(0, start);
+    }
+    if path.last() != Some(&goal) {
+        path.push(goal);
     }
+    path
 }
 
 impl AcoStrategy {
-    fn path_desire(&self, path: Path, path_pheromones: &HashMap<Path, f64>, goal: Vec2) -> f64 {
-        let pheromone = path_pheromones.get(&path).unwrap_or(&self.init_pheromone).powf(self.alpha);
-        let heuristic = (1.0 / self.node_to_world_pos(path.to).distance(goal) as f64).powf(self.beta);
-        pheromone * heuristic
+    fn path_desire<S: SearchSpace>(
+        &self,
+        space: &S,
+        problem: &Problem,
+        from: &S::Node,
+        to: &S::Node,
+        path_pheromones: &HashMap<(S::Node, S::Node), f64>,
+        goal: Vec2,
+    ) -> f64 {
+        let pheromone = path_pheromones
+            .get(&(from.clone(), to.clone()))
+            .unwrap_or(&self.init_pheromone)
+            .powf(self.alpha);
+        // Estimates under one unit count as one, so the zero heuristic leaves the choice to the pheromone
+        let estimate = space.heuristic(problem, &self.heuristic, to, goal) as f64;
+        pheromone * (1.0 / estimate.max(1.0)).powf(self.beta)
     }
 
-    fn get_next_node(&self, node_desires: &[(Node, f64)], total_desire: f64) -> Node {
-        let random: f64 = rand::gen_range(0.0, total_desire);
+    fn get_next_node<N: Clone>(&self, rng: &RandGenerator, node_desires: &[(N, f32, f64)], total_desire: f64) -> (N, f32) {
+        let random: f64 = rng.gen_range(0.0, total_desire);
 
         let mut accumulated_probability = 0.0;
-        for (node, disire) in node_desires {
+        for (node, cost, disire) in node_desires {
             accumulated_probability += disire;
             if random <= accumulated_probability {
-                return node.clone();
+                return (node.clone(), *cost);
             }
         }
 
-        node_desires.last().unwrap().0.clone()
+        let (node, cost, _) = node_desires.last().unwrap();
+        (node.clone(), *cost)
     }
 
-    fn update_pheromone(&self, route: &[Node], route_len: f64, path_pheromones: &mut HashMap<Path, f64>) {
+    fn update_pheromone<S: SearchSpace>(
+        &self,
+        space: &S,
+        route: &[S::Node],
+        route_len: f64,
+        path_pheromones: &mut HashMap<(S::Node, S::Node), f64>,
+        context: &PlanContext,
+    ) {
         path_pheromones.iter_mut().for_each(|(_, pheromone)| {
             *pheromone *= 1.0 - self.evaporation;
         });
 
         for nodes in route.windows(2) {
-            let path = Path::new(nodes[0].clone(), nodes[1].clone());
+            let path = (nodes[0].clone(), nodes[1].clone());
             let deposit_pheromone = self.deposit_constant / (route_len);
-            if let Some(pheromone) = path_pheromones.get_mut(&path) {
+            let pheromone = if let Some(pheromone) = path_pheromones.get_mut(&path) {
                 *pheromone += deposit_pheromone;
+                *pheromone
             } else {
-                path_pheromones.insert(path.clone(), self.init_pheromone + deposit_pheromone);
-            }
+                path_pheromones.insert(path, self.init_pheromone + deposit_pheromone);
+                self.init_pheromone + deposit_pheromone
+            };
+            context.emit(|observer| {
+                observer.pheromone_updated(space.position(&nodes[0]), space.position(&nodes[1]), pheromone)
+            });
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gridmaker;
+    use crate::world::WorldConfig;
+    use crate::world::grid::{GridMap, GridNodeValue};
+    use std::sync::Arc;
+
+    fn corridors_problem() -> Problem {
+        let preset = gridmaker::preset("corridors", 25).unwrap();
+        let (start, goal) = preset.suggested_queries[0];
+        Problem::new(Arc::new(preset.grid_map), start, goal)
+    }
+
+    fn small_colony(seed: u64) -> AcoStrategy {
+        AcoStrategy {
+            min_ant_count: 100,
+            seed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_path() {
+        let problem = corridors_problem();
+        let first = small_colony(7).path_finding(&problem, &PlanContext::new()).unwrap();
+        let second = small_colony(7).path_finding(&problem, &PlanContext::new()).unwrap();
+        assert_eq!(first.path, second.path);
+        assert_eq!(first.seed, Some(7));
+        assert_eq!(second.seed, Some(7));
+    }
+
+    #[test]
+    fn improvements_run_from_the_start_to_the_goal() {
+        let problem = corridors_problem();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let context = PlanContext::new().with_improvements(sender);
+        let result = small_colony(3).path_finding(&problem, &context).unwrap();
+
+        let improvements: Vec<_> = receiver.try_iter().collect();
+        assert!(!improvements.is_empty());
+        for improvement in &improvements {
+            assert!(PathValidator::default().violations(&problem, &improvement.result.path).is_empty());
+        }
+        assert_eq!(improvements.last().unwrap().result.path, result.path);
+        // The best route's cost includes the hops linking it to the start and goal
+        let best = *result.stats.best_per_iteration.last().unwrap();
+        assert!((best - result.cost as f64).abs() < 1e-2);
+    }
+
+    #[test]
+    fn links_the_start_to_a_reachable_lattice_point() {
+        // Obstacles hide the four lattice points around the start from it
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (30, 30),
+            cell_size: 10.0,
+        });
+        for (x, y) in [(3, 3), (5, 3), (3, 5), (5, 5)] {
+            grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+        }
+        let problem = Problem::new(Arc::new(grid_map), Vec2::new(45.0, 45.0), Vec2::new(255.0, 255.0));
+        let result = small_colony(1).path_finding(&problem, &PlanContext::new()).unwrap();
+        assert!(PathValidator::default().violations(&problem, &result.path).is_empty());
+    }
+
+    #[test]
+    fn context_seed_overrides_the_configured_one() {
+        let problem = corridors_problem();
+        let context = PlanContext::new().with_seed(11);
+        let overridden = small_colony(7).path_finding(&problem, &context).unwrap();
+        let configured = small_colony(11).path_finding(&problem, &PlanContext::new()).unwrap();
+        assert_eq!(overridden.seed, Some(11));
+        assert_eq!(overridden.path, configured.path);
+    }
+}
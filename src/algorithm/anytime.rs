@@ -0,0 +1,61 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use std::sync::{Arc, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+/// A solution better than every one streamed before it in the same run.
+#[derive(Debug, Clone)]
+pub struct Improvement {
+    /// Index of the pipeline stage that found it, 0 outside pipelines.
+    pub stage: usize,
+    /// Iterations the strategy had completed when it found this solution, ants for ACO.
+    pub iteration: usize,
+    pub timestamp: Instant,
+    pub result: PlanResult,
+}
+
+/// Strategy running on its own thread, iterating blocks for its next improvement until the run ends.
+pub struct AnytimePlan {
+    improvements: mpsc::Receiver<Improvement>,
+    cancel: CancellationToken,
+    handle: JoinHandle<Result<PlanResult, PlanError>>,
+}
+
+impl AnytimePlan {
+    /// Starts `strategy` with `context`, reusing its cancellation token if it has one.
+    pub fn spawn<S: Strategy + 'static>(strategy: S, problem: Arc<Problem>, context: PlanContext) -> Self {
+        let (sender, improvements) = mpsc::channel();
+        let cancel = context.cancellation().cloned().unwrap_or_default();
+        let context = context.with_cancellation(cancel.clone()).with_improvements(sender);
+        let handle = thread::spawn(move || strategy.path_finding(&problem, &context));
+
+        Self {
+            improvements,
+            cancel,
+            handle,
+        }
+    }
+
+    /// Next improvement if one is already waiting, for callers that poll once per frame.
+    pub fn try_next(&self) -> Option<Improvement> { self.improvements.try_recv().ok() }
+
+    /// Asks the strategy to stop, the improvements streamed so far stay valid.
+    pub fn stop(&self) { self.cancel.cancel(); }
+
+    pub fn is_finished(&self) -> bool { self.handle.is_finished() }
+
+    /// Waits for the strategy and returns its final answer, `Err(Cancelled)` after `stop`.
+    pub fn join(self) -> Result<PlanResult, PlanError> {
+        self.handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+impl Iterator for AnytimePlan {
+    type Item = Improvement;
+
+    fn next(&mut self) -> Option<Improvement> { self.improvements.recv().ok() }
+}
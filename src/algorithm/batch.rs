@@ -0,0 +1,132 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use crate::world::geometry::Geometry;
+use glam::Vec2;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Solves many start/goal queries against one shared world on a pool of worker threads.
+#[derive(Debug, Clone, Default)]
+pub struct BatchPlanner {
+    /// Worker threads, 0 uses every available core.
+    pub workers: usize,
+    /// Base seed every query derives its own seed from, `None` leaves the strategy's configured seed.
+    /// Either way each query draws from its own random stream, so results do not depend on scheduling.
+    pub seed: Option<u64>,
+}
+
+impl BatchPlanner {
+    pub fn new(workers: usize) -> Self { Self { workers, seed: None } }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Results are in query order. The context is shared, so cancelling it or reaching its deadline
+    /// stops every query that has not finished yet.
+    pub fn plan(
+        &self,
+        strategy: &dyn Strategy,
+        world: Arc<dyn Geometry>,
+        queries: &[(Vec2, Vec2)],
+        context: &PlanContext,
+    ) -> Vec<Result<PlanResult, PlanError>> {
+        let workers = match self.workers {
+            0 => thread::available_parallelism().map_or(1, |count| count.get()),
+            workers => workers,
+        }
+        .min(queries.len());
+
+        let next_query = AtomicUsize::new(0);
+        let solved: Vec<Vec<(usize, Result<PlanResult, PlanError>)>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut solved = Vec::new();
+                        loop {
+                            let index = next_query.fetch_add(1, Ordering::Relaxed);
+                            let Some(&(start, goal)) = queries.get(index) else {
+                                break;
+                            };
+                            let problem = Problem::new(Arc::clone(&world), start, goal);
+                            let context = match self.seed {
+                                Some(seed) => context.clone().with_seed(query_seed(seed, index)),
+                                None => context.clone(),
+                            };
+                            solved.push((index, strategy.path_finding(&problem, &context)));
+                        }
+                        solved
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+
+        let mut results: Vec<Option<Result<PlanResult, PlanError>>> = (0..queries.len()).map(|_| None).collect();
+        for (index, result) in solved.into_iter().flatten() {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("every query is taken by exactly one worker"))
+            .collect()
+    }
+}
+
+/// SplitMix64 of the base seed and the query index, neighbouring queries get unrelated streams.
+fn query_seed(seed: u64, index: usize) -> u64 {
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::aco::AcoStrategy;
+    use crate::gridmaker;
+    use std::collections::HashSet;
+
+    #[test]
+    fn seeded_batches_are_reproducible_and_independent_per_query() {
+        let preset = gridmaker::preset("corridors", 25).unwrap();
+        let world: Arc<dyn Geometry> = Arc::new(preset.grid_map);
+        // The same query three times, only the query index tells their seeds apart
+        let queries = vec![preset.suggested_queries[0]; 3];
+        let aco = AcoStrategy {
+            min_ant_count: 50,
+            ..Default::default()
+        };
+
+        let plan = |workers: usize, queries: &[(Vec2, Vec2)]| -> Vec<PlanResult> {
+            BatchPlanner::new(workers)
+                .with_seed(3)
+                .plan(&aco, Arc::clone(&world), queries, &PlanContext::new())
+                .into_iter()
+                .map(|result| result.unwrap())
+                .collect()
+        };
+        let first = plan(3, &queries);
+        let second = plan(1, &queries);
+        let alone = plan(1, &queries[..1]);
+
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.seed, b.seed);
+            assert_eq!(a.path, b.path);
+        }
+        assert_eq!(alone[0].path, first[0].path);
+
+        let seeds: HashSet<Option<u64>> = first.iter().map(|result| result.seed).collect();
+        assert_eq!(seeds.len(), queries.len());
+        assert!(!seeds.contains(&Some(3)));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::algorithm::anytime::Improvement;
+use crate::algorithm::error::PlanError;
+use crate::algorithm::observer::PlannerObserver;
+use crate::algorithm::result::PlanResult;
+use std::sync::{Arc, mpsc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Shared flag another thread sets to stop a running strategy, clones observe the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn cancel(&self) { self.cancelled.store(true, Ordering::Relaxed); }
+
+    pub fn is_cancelled(&self) -> bool { self.cancelled.load(Ordering::Relaxed) }
+}
+
+/// Limits a strategy checks cooperatively while it searches, the default context never stops it.
+///
+/// A strategy stopped by the deadline or the expansion budget returns the best path found so far,
+/// or the budget error if it has none yet. A cancelled strategy always returns `PlanError::Cancelled`.
+#[derive(Clone, Default)]
+pub struct PlanContext {
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    max_expansions: Option<usize>,
+    observer: Option<Arc<dyn PlannerObserver>>,
+    improvements: Option<mpsc::Sender<Improvement>>,
+    stage: usize,
+    seed: Option<u64>,
+}
+
+impl PlanContext {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Deadline `budget` from now.
+    pub fn with_time_budget(self, budget: Duration) -> Self { self.with_deadline(Instant::now() + budget) }
+
+    /// Caps the search work, counted as nodes expanded by A*, ants dispatched by ACO and iterations of optimizers.
+    /// Every stage of a `Pipeline` gets the full budget.
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = Some(max_expansions);
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn PlannerObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Streams every new best solution of ACO and PSO, and the single answer of A*, while they run.
+    pub fn with_improvements(mut self, sender: mpsc::Sender<Improvement>) -> Self {
+        self.improvements = Some(sender);
+        self
+    }
+
+    /// Tags the improvements sent through this context with the pipeline stage index `stage`.
+    pub fn with_stage(mut self, stage: usize) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    pub fn stage(&self) -> usize { self.stage }
+
+    /// Overrides the configured seed of randomized strategies, giving every call its own random stream.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn seed(&self) -> Option<u64> { self.seed }
+
+    pub fn cancellation(&self) -> Option<&CancellationToken> { self.cancellation.as_ref() }
+
+    pub fn deadline(&self) -> Option<Instant> { self.deadline }
+
+    pub fn max_expansions(&self) -> Option<usize> { self.max_expansions }
+
+    pub fn observer(&self) -> Option<&dyn PlannerObserver> { self.observer.as_deref() }
+
+    /// Runs `event` against the observer, skipped entirely without one so callers can build event data lazily.
+    pub fn emit(&self, event: impl FnOnce(&dyn PlannerObserver)) {
+        if let Some(observer) = &self.observer {
+            event(observer.as_ref());
+        }
+    }
+
+    /// Sends the result built by `result` as the newest improvement, built only when someone listens.
+    pub fn report_improvement(&self, result: impl FnOnce() -> PlanResult) {
+        if let Some(sender) = &self.improvements {
+            let result = result();
+            // A dropped receiver only means nobody watches the progress anymore
+            let _ = sender.send(Improvement {
+                stage: self.stage,
+                iteration: result.stats.iterations,
+                timestamp: Instant::now(),
+                result,
+            });
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool { self.cancellation.as_ref().is_some_and(|token| token.is_cancelled()) }
+
+    /// Why the search has to stop after `expansions` units of work, `None` while it may go on.
+    pub fn stop_reason(&self, expansions: usize) -> Option<PlanError> {
+        if self.is_cancelled() {
+            Some(PlanError::Cancelled)
+        } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(PlanError::TimeBudgetExhausted)
+        } else if self.max_expansions.is_some_and(|max| expansions >= max) {
+            Some(PlanError::IterationBudgetExhausted)
+        } else {
+            None
+        }
+    }
+
+    pub fn check(&self, expansions: usize) -> Result<(), PlanError> { self.stop_reason(expansions).map_or(Ok(()), Err) }
+}
@@ -0,0 +1,54 @@
+use crate::algorithm::validator::PathViolation;
+use glam::Vec2;
+use std::fmt;
+
+/// Why a strategy could not produce a path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanError {
+    /// Start is not a finite point inside the world bounds.
+    InvalidStart(Vec2),
+    InvalidGoal(Vec2),
+    StartInObstacle(Vec2),
+    GoalInObstacle(Vec2),
+    /// The input path handed to a path optimizer has fewer than two points.
+    InvalidInitialPath,
+    /// The search ran out of options, no path exists under the strategy's constraints.
+    Unreachable,
+    /// Every iteration, ant or particle the strategy was allowed was used without reaching the goal.
+    IterationBudgetExhausted,
+    TimeBudgetExhausted,
+    Cancelled,
+    /// The strategy's output failed validation, never empty.
+    InvalidPath(Vec<PathViolation>),
+    /// The strategy panicked, holds the panic message.
+    Panicked(String),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::InvalidStart(pos) => write!(f, "start {} is outside the world", pos),
+            PlanError::InvalidGoal(pos) => write!(f, "goal {} is outside the world", pos),
+            PlanError::StartInObstacle(pos) => write!(f, "start {} is inside an obstacle", pos),
+            PlanError::GoalInObstacle(pos) => write!(f, "goal {} is inside an obstacle", pos),
+            PlanError::InvalidInitialPath => write!(f, "initial path needs at least two points"),
+            PlanError::Unreachable => write!(f, "goal is unreachable"),
+            PlanError::IterationBudgetExhausted => write!(f, "iteration budget exhausted before reaching the goal"),
+            PlanError::TimeBudgetExhausted => write!(f, "time budget exhausted before reaching the goal"),
+            PlanError::Cancelled => write!(f, "planning was cancelled"),
+            PlanError::InvalidPath(violations) => {
+                write!(f, "path failed validation")?;
+                if let Some(first) = violations.first() {
+                    write!(f, ": {}", first)?;
+                }
+                if violations.len() > 1 {
+                    write!(f, " and {} more", violations.len() - 1)?;
+                }
+                Ok(())
+            }
+            PlanError::Panicked(message) => write!(f, "strategy panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
@@ -0,0 +1,105 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Estimate of the travel distance between two points, before any zone cost factor is applied.
+///
+/// Only estimates never above the Euclidean distance keep A* optimal on every search space, larger ones
+/// trade path quality for fewer expansions.
+pub trait Heuristic: Send + Sync {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32;
+}
+
+impl<H: Heuristic + ?Sized> Heuristic for &H {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 { (**self).estimate(from, to) }
+}
+
+/// Straight line distance.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+impl Heuristic for Euclidean {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 { from.distance(to) }
+}
+
+/// Distance moving along the axes and the diagonals only, exact on eight-connected grids.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Octile;
+
+impl Heuristic for Octile {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 {
+        let delta = (to - from).abs();
+        delta.max_element() + (std::f32::consts::SQRT_2 - 1.0) * delta.min_element()
+    }
+}
+
+/// Distance moving along the axes only, exact on four-connected grids.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 {
+        let delta = (to - from).abs();
+        delta.x + delta.y
+    }
+}
+
+/// Largest axis distance, diagonal steps count as long as axis steps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chebyshev;
+
+impl Heuristic for Chebyshev {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 { (to - from).abs().max_element() }
+}
+
+/// No estimate at all, A* degrades to Dijkstra and ants follow the pheromone alone.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Zero;
+
+impl Heuristic for Zero {
+    fn estimate(&self, _from: Vec2, _to: Vec2) -> f32 { 0.0 }
+}
+
+/// Scales another heuristic, a weight above 1 makes A* greedier.
+///
+/// Ants compare estimates with each other, which a weight scales alike, but they clamp estimates to at least 1.
+/// Close to the goal a weight therefore still shifts their choices.
+#[derive(Debug, Clone, Copy)]
+pub struct Weighted<H> {
+    pub base: H,
+    pub weight: f32,
+}
+
+impl<H: Heuristic> Heuristic for Weighted<H> {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 { self.base.estimate(from, to) * self.weight }
+}
+
+/// Heuristic selected by name in a strategy config, e.g. `"octile"` or
+/// `{"weighted": {"weight": 1.5, "base": "euclidean"}}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum HeuristicKind {
+    #[default]
+    Euclidean,
+    Octile,
+    Manhattan,
+    Chebyshev,
+    Zero,
+    Weighted { weight: f32, base: Box<HeuristicKind> },
+}
+
+impl Heuristic for HeuristicKind {
+    fn estimate(&self, from: Vec2, to: Vec2) -> f32 {
+        match self {
+            HeuristicKind::Euclidean => Euclidean.estimate(from, to),
+            HeuristicKind::Octile => Octile.estimate(from, to),
+            HeuristicKind::Manhattan => Manhattan.estimate(from, to),
+            HeuristicKind::Chebyshev => Chebyshev.estimate(from, to),
+            HeuristicKind::Zero => Zero.estimate(from, to),
+            HeuristicKind::Weighted { weight, base } => Weighted {
+                base: base.as_ref(),
+                weight: *weight,
+            }
+            .estimate(from, to),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::{CountingGeometry, Geometry};
+use crate::world::medial_axis::MedialAxisRoadmap;
+use glam::Vec2;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Max-clearance routes on a prebuilt medial axis roadmap, also a safe initial path for `PsoStrategy::upgrade_path`.
+pub struct MedialAxisStrategy {
+    roadmap: Arc<MedialAxisRoadmap>,
+}
+
+impl MedialAxisStrategy {
+    pub fn new(roadmap: MedialAxisRoadmap) -> Self {
+        Self {
+            roadmap: Arc::new(roadmap),
+        }
+    }
+
+    pub fn with_shared(roadmap: Arc<MedialAxisRoadmap>) -> Self { Self { roadmap } }
+
+    pub fn roadmap(&self) -> &MedialAxisRoadmap { &self.roadmap }
+}
+
+impl Strategy for MedialAxisStrategy {
+    /// The roadmap ignores zones, a path crossing a no-go zone or blocked in the problem's world is rejected rather
+    /// than rerouted.
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        context.check(0)?;
+
+        let world = CountingGeometry::new(problem.world());
+        let (path, expanded) = self.roadmap.find_path(problem.start(), problem.goal()).ok_or(PlanError::Unreachable)?;
+        let passable = |segment: &[Vec2]| {
+            world.is_segment_clear(segment[0], segment[1], 0.0) && problem.is_segment_allowed(segment[0], segment[1])
+        };
+        if !path.windows(2).all(passable) {
+            return Err(PlanError::Unreachable);
+        }
+
+        let stats = SearchStats {
+            nodes_expanded: expanded,
+            segment_checks: world.segment_checks(),
+            ..Default::default()
+        };
+        certify(problem, PlanResult::new(problem, path, started.elapsed(), stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gridmaker;
+    use crate::world::medial_axis::MedialAxisConfig;
+
+    #[test]
+    fn reports_the_search_effort() {
+        let preset = gridmaker::preset("rooms", 36).unwrap();
+        let (start, goal) = preset.suggested_queries[0];
+        let strategy = MedialAxisStrategy::new(MedialAxisRoadmap::new(&preset.grid_map, MedialAxisConfig::default()));
+        let problem = Problem::new(Arc::new(preset.grid_map), start, goal);
+
+        let result = strategy.path_finding(&problem, &PlanContext::new()).unwrap();
+        assert!(result.stats.nodes_expanded > 0);
+        assert_eq!(result.stats.segment_checks, result.path.len() - 1);
+    }
+}
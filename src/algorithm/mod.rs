@@ -1,5 +1,24 @@
 pub mod a_star;
 pub mod aco;
+pub mod anytime;
+pub mod batch;
+pub mod context;
+pub mod error;
+pub mod heuristic;
+pub mod medial_axis;
+pub mod navmesh;
+pub mod observer;
+pub mod optimizer;
+pub mod path_metrics;
+pub mod pipeline;
 pub mod problem;
 pub mod pso;
+pub mod registry;
+pub mod result;
+pub mod search_space;
+pub mod service;
+pub mod shortcut;
+pub mod smoothing;
 pub mod strategy;
+pub mod validator;
+pub mod visibility;
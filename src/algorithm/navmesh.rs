@@ -0,0 +1,72 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::{CountingGeometry, Geometry};
+use crate::world::navmesh::NavMesh;
+use glam::Vec2;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Plans on a prebuilt navigation mesh, the mesh has to be built from the same world as the problem.
+pub struct NavMeshStrategy {
+    navmesh: Arc<NavMesh>,
+}
+
+impl NavMeshStrategy {
+    pub fn new(navmesh: NavMesh) -> Self {
+        Self {
+            navmesh: Arc::new(navmesh),
+        }
+    }
+
+    pub fn with_shared(navmesh: Arc<NavMesh>) -> Self { Self { navmesh } }
+
+    pub fn navmesh(&self) -> &NavMesh { &self.navmesh }
+}
+
+impl Strategy for NavMeshStrategy {
+    /// The mesh ignores zones, a path crossing a no-go zone or blocked in the problem's world is rejected rather than
+    /// rerouted.
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        context.check(0)?;
+
+        let world = CountingGeometry::new(problem.world());
+        let (path, expanded) = self.navmesh.find_path(problem.start(), problem.goal()).ok_or(PlanError::Unreachable)?;
+        let passable = |segment: &[Vec2]| {
+            world.is_segment_clear(segment[0], segment[1], 0.0) && problem.is_segment_allowed(segment[0], segment[1])
+        };
+        if !path.windows(2).all(passable) {
+            return Err(PlanError::Unreachable);
+        }
+
+        let stats = SearchStats {
+            nodes_expanded: expanded,
+            segment_checks: world.segment_checks(),
+            ..Default::default()
+        };
+        certify(problem, PlanResult::new(problem, path, started.elapsed(), stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gridmaker;
+
+    #[test]
+    fn reports_the_search_effort() {
+        let preset = gridmaker::preset("rooms", 36).unwrap();
+        let (start, goal) = preset.suggested_queries[0];
+        let strategy = NavMeshStrategy::new(NavMesh::new(&preset.grid_map, 0.0));
+        let problem = Problem::new(Arc::new(preset.grid_map), start, goal);
+
+        let result = strategy.path_finding(&problem, &PlanContext::new()).unwrap();
+        assert!(result.stats.nodes_expanded > 0);
+        assert_eq!(result.stats.segment_checks, result.path.len() - 1);
+    }
+}
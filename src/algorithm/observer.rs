@@ -0,0 +1,24 @@
+use glam::Vec2;
+
+/// Receives progress events from a running strategy, every hook defaults to doing nothing.
+///
+/// Hooks run on the planning thread inside the search loop, so they should return quickly.
+pub trait PlannerObserver: Send + Sync {
+    /// A* took the node at `pos` off its open list.
+    fn node_expanded(&self, _pos: Vec2) {}
+
+    /// Ant number `ant` stepped from `from` to `to`.
+    fn ant_moved(&self, _ant: usize, _from: Vec2, _to: Vec2) {}
+
+    /// Ant number `ant` stopped walking, `route` is every node it visited in order.
+    fn ant_finished(&self, _ant: usize, _route: &[Vec2], _reached_goal: bool) {}
+
+    /// The edge `from` to `to` holds `pheromone` after a deposit.
+    fn pheromone_updated(&self, _from: Vec2, _to: Vec2, _pheromone: f64) {}
+
+    /// Particle number `particle` moved to the waypoints `path`.
+    fn particle_moved(&self, _particle: usize, _path: &[Vec2]) {}
+
+    /// The strategy found a better path than any before, `cost` is in the strategy's own measure.
+    fn new_best(&self, _path: &[Vec2], _cost: f64) {}
+}
@@ -0,0 +1,13 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use glam::Vec2;
+
+pub type BoxedOptimizer = Box<dyn PathOptimizer>;
+
+/// Improves an existing path for a problem, the stages after the first one of a `Pipeline`.
+pub trait PathOptimizer: Send + Sync {
+    /// `path` runs from the problem's start to its goal, fewer than two points is `PlanError::InvalidInitialPath`.
+    fn optimize(&self, problem: &Problem, path: &[Vec2], context: &PlanContext) -> Result<PlanResult, PlanError>;
+}
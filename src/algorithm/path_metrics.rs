@@ -0,0 +1,276 @@
+use crate::algorithm::result::path_length;
+use crate::world::clearance::ClearanceField;
+use glam::Vec2;
+use serde::Serialize;
+
+/// Heading changes below this many radians are numerical noise, not turns.
+const TURN_EPSILON: f32 = 1e-3;
+
+/// Discrete curvature at the interior waypoints, turning angle over the mean length of the two adjacent segments.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CurvatureStats {
+    pub mean: f32,
+    pub max: f32,
+    pub std_dev: f32,
+}
+
+/// Shape and safety of a path, one definition of "path quality" for fitness functions, reports and the UI.
+/// Angles are in radians, lengths and clearances in world units.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PathMetrics {
+    pub length: f32,
+    pub waypoints: usize,
+    /// Waypoints where the heading changes.
+    pub turns: usize,
+    pub total_turn: f32,
+    pub max_turn: f32,
+    pub curvature: CurvatureStats,
+    /// Smallest gap to an obstacle anywhere along the path.
+    pub min_clearance: f32,
+    /// Gap to the nearest obstacle averaged over the path's length.
+    pub mean_clearance: f32,
+    /// Clearance the tight share is measured against.
+    pub clearance_threshold: f32,
+    /// Share of the length closer to an obstacle than `clearance_threshold`.
+    pub tight_fraction: f32,
+}
+
+impl PathMetrics {
+    /// Clearance is sampled at least twice per cell of `clearance` along every segment.
+    pub fn new(path: &[Vec2], clearance: &ClearanceField, clearance_threshold: f32) -> Self {
+        let turns = turn_angles(path);
+        let curvature = curvatures(path);
+        let (min_clearance, mean_clearance, tight_fraction) = clearance_along(path, clearance, clearance_threshold);
+
+        Self {
+            length: path_length(path),
+            waypoints: path.len(),
+            turns: turns.iter().filter(|&&angle| angle > TURN_EPSILON).count(),
+            total_turn: turns.iter().fold(0.0, |total, angle| total + angle),
+            max_turn: turns.iter().copied().fold(0.0, f32::max),
+            curvature: curvature_stats(&curvature),
+            min_clearance,
+            mean_clearance,
+            clearance_threshold,
+            tight_fraction,
+        }
+    }
+
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).unwrap_or_default() }
+
+    /// One line overview for logs and the demo UI, angles in degrees.
+    pub fn summary(&self) -> String {
+        format!(
+            "turns {} ({:.0}° total, {:.0}° max) clearance min {:.0} mean {:.0}, {:.0}% under {:.0}",
+            self.turns,
+            self.total_turn.to_degrees(),
+            self.max_turn.to_degrees(),
+            self.min_clearance,
+            self.mean_clearance,
+            self.tight_fraction * 100.0,
+            self.clearance_threshold
+        )
+    }
+}
+
+/// Heading change at every interior waypoint, in `[0, pi]`. Repeated waypoints are skipped.
+pub fn turn_angles(path: &[Vec2]) -> Vec<f32> {
+    let headings: Vec<Vec2> = path
+        .windows(2)
+        .filter_map(|seg| (seg[1] - seg[0]).try_normalize())
+        .collect();
+    headings
+        .windows(2)
+        .map(|pair| pair[0].dot(pair[1]).clamp(-1.0, 1.0).acos())
+        .collect()
+}
+
+pub fn total_turn(path: &[Vec2]) -> f32 { turn_angles(path).iter().fold(0.0, |total, angle| total + angle) }
+
+fn curvatures(path: &[Vec2]) -> Vec<f32> {
+    let segments: Vec<Vec2> = path
+        .windows(2)
+        .map(|seg| seg[1] - seg[0])
+        .filter(|seg| seg.length_squared() > 0.0)
+        .collect();
+    segments
+        .windows(2)
+        .map(|pair| {
+            let angle = pair[0].normalize().dot(pair[1].normalize()).clamp(-1.0, 1.0).acos();
+            angle / ((pair[0].length() + pair[1].length()) / 2.0)
+        })
+        .collect()
+}
+
+fn curvature_stats(curvature: &[f32]) -> CurvatureStats {
+    if curvature.is_empty() {
+        return CurvatureStats::default();
+    }
+
+    let count = curvature.len() as f32;
+    let mean = curvature.iter().sum::<f32>() / count;
+    let variance = curvature.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / count;
+    CurvatureStats {
+        mean,
+        max: curvature.iter().copied().fold(0.0, f32::max),
+        std_dev: variance.sqrt(),
+    }
+}
+
+/// Minimum, length weighted mean and share of length under `threshold`, from samples at the middle of
+/// equal pieces of every segment.
+fn clearance_along(path: &[Vec2], clearance: &ClearanceField, threshold: f32) -> (f32, f32, f32) {
+    let Some(&first) = path.first() else {
+        return (0.0, 0.0, 0.0);
+    };
+
+    let step = clearance.cell_size() / 2.0;
+    let mut min_clearance = path.iter().map(|&pos| clearance.clearance_at(pos)).fold(f32::INFINITY, f32::min);
+    let mut weighted_sum = 0.0;
+    let mut tight_length = 0.0;
+    let mut length = 0.0;
+    for seg in path.windows(2) {
+        let seg_length = seg[0].distance(seg[1]);
+        if seg_length <= 0.0 {
+            continue;
+        }
+        let pieces = (seg_length / step).ceil().max(1.0) as usize;
+        let piece_length = seg_length / pieces as f32;
+        for piece in 0..pieces {
+            let sample = seg[0].lerp(seg[1], (piece as f32 + 0.5) / pieces as f32);
+            let gap = clearance.clearance_at(sample);
+            min_clearance = min_clearance.min(gap);
+            weighted_sum += gap * piece_length;
+            if gap < threshold {
+                tight_length += piece_length;
+            }
+        }
+        length += seg_length;
+    }
+
+    if length <= 0.0 {
+        // A single point, or the same point repeated
+        let gap = clearance.clearance_at(first);
+        return (gap, gap, if gap < threshold { 1.0 } else { 0.0 });
+    }
+    (min_clearance, weighted_sum / length, tight_length / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+    use crate::world::grid::{GridMap, GridNodeValue};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    /// 110 x 110 world without obstacles, only its border limits the clearance.
+    fn open_clearance() -> ClearanceField {
+        ClearanceField::new(&GridMap::new(WorldConfig {
+            grid_size: (11, 11),
+            cell_size: 10.0,
+        }))
+    }
+
+    #[test]
+    fn straight_line_has_no_turns() {
+        let path = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(30.0, 0.0)];
+        assert_eq!(turn_angles(&path).len(), 1);
+        assert_close(turn_angles(&path)[0], 0.0);
+        assert_close(total_turn(&path), 0.0);
+        assert_eq!(PathMetrics::new(&path, &open_clearance(), 0.0).turns, 0);
+    }
+
+    #[test]
+    fn l_shape_turns_once_by_a_right_angle() {
+        let path = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        assert_eq!(turn_angles(&path).len(), 1);
+        assert_close(turn_angles(&path)[0], FRAC_PI_2);
+
+        let metrics = PathMetrics::new(&path, &open_clearance(), 0.0);
+        assert_eq!(metrics.turns, 1);
+        assert_close(metrics.total_turn, FRAC_PI_2);
+        assert_close(metrics.max_turn, FRAC_PI_2);
+        assert_close(metrics.length, 20.0);
+        // Right angle spread over the mean length of two 10 unit segments
+        assert_close(metrics.curvature.max, FRAC_PI_2 / 10.0);
+    }
+
+    #[test]
+    fn repeated_waypoints_and_reversals() {
+        let path = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 0.0),
+        ];
+        assert_eq!(turn_angles(&path).len(), 1);
+        assert_close(turn_angles(&path)[0], PI);
+        assert!(turn_angles(&[Vec2::ZERO]).is_empty());
+        assert_close(total_turn(&[]), 0.0);
+    }
+
+    #[test]
+    fn curvature_stats_summarize_the_samples() {
+        let stats = curvature_stats(&[1.0, 3.0]);
+        assert_close(stats.mean, 2.0);
+        assert_close(stats.max, 3.0);
+        assert_close(stats.std_dev, 1.0);
+
+        let empty = curvature_stats(&[]);
+        assert_close(empty.mean, 0.0);
+        assert_close(empty.max, 0.0);
+        assert_close(empty.std_dev, 0.0);
+    }
+
+    #[test]
+    fn clearance_along_a_column_through_the_middle() {
+        // Cells 2 to 8 of the middle column are 25, 35, 45, 55, 45, 35 and 25 units from the border
+        let path = [Vec2::new(55.0, 25.0), Vec2::new(55.0, 85.0)];
+        let (min, mean, tight) = clearance_along(&path, &open_clearance(), 30.0);
+        assert_close(min, 25.0);
+        assert_close(mean, 40.0);
+        assert_close(tight, 2.0 / 12.0);
+    }
+
+    /// 400 x 300 world of 100 unit cells with a single obstacle cell at x 100..200, y 100..200.
+    fn block_clearance() -> ClearanceField {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (4, 3),
+            cell_size: 100.0,
+        });
+        grid_map.grid_mut().set(1, 1, GridNodeValue::Obstacle);
+        ClearanceField::new(&grid_map)
+    }
+
+    #[test]
+    fn clearance_is_measured_from_the_sample_not_the_cell_center() {
+        let clearance = block_clearance();
+        assert_close(clearance.clearance_at(Vec2::new(201.0, 150.0)), 1.0);
+        assert_close(clearance.clearance_at(Vec2::new(203.0, 204.0)), 5.0);
+        assert_close(clearance.clearance_at(Vec2::new(150.0, 150.0)), 0.0);
+        // Closer to the map edge than to the block
+        assert_close(clearance.clearance_at(Vec2::new(250.0, 290.0)), 10.0);
+    }
+
+    #[test]
+    fn path_hugging_an_obstacle_is_tight() {
+        // Runs along the block's right side one unit away, the cell centers it passes are 50 units from it
+        let path = [Vec2::new(201.0, 110.0), Vec2::new(201.0, 190.0)];
+        let (min, mean, tight) = clearance_along(&path, &block_clearance(), 30.0);
+        assert_close(min, 1.0);
+        assert_close(mean, 1.0);
+        assert_close(tight, 1.0);
+    }
+
+    #[test]
+    fn clearance_of_a_single_point() {
+        let clearance = open_clearance();
+        assert_eq!(clearance_along(&[Vec2::new(55.0, 55.0)], &clearance, 30.0), (55.0, 55.0, 0.0));
+        assert_eq!(clearance_along(&[Vec2::new(5.0, 5.0)], &clearance, 30.0), (5.0, 5.0, 1.0));
+        assert_eq!(clearance_along(&[], &clearance, 30.0), (0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::optimizer::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use std::time::Instant;
+
+/// A planner followed by a chain of optimizers, each stage refining the path of the one before.
+///
+/// The pipeline's result holds the last stage's path with the summed statistics of all stages, and every
+/// stage's own result in `stages`. Improvements streamed through the context are tagged with the stage index.
+pub struct Pipeline {
+    planner: (String, BoxedStrategy),
+    optimizers: Vec<(String, BoxedOptimizer)>,
+}
+
+impl Pipeline {
+    pub fn new(name: &str, planner: BoxedStrategy) -> Self {
+        Self {
+            planner: (name.to_string(), planner),
+            optimizers: Vec::new(),
+        }
+    }
+
+    /// Appends an optimizer stage.
+    pub fn then(mut self, name: &str, optimizer: BoxedOptimizer) -> Self {
+        self.optimizers.push((name.to_string(), optimizer));
+        self
+    }
+
+    /// Stage names in run order, the planner first.
+    pub fn stage_names(&self) -> Vec<&str> {
+        std::iter::once(self.planner.0.as_str())
+            .chain(self.optimizers.iter().map(|(name, _)| name.as_str()))
+            .collect()
+    }
+}
+
+impl Strategy for Pipeline {
+    /// A failing stage fails the whole pipeline, the stages that ran before it are dropped.
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+
+        let (planner_name, planner) = &self.planner;
+        let planned = planner.path_finding(problem, &context.clone().with_stage(0))?;
+        let mut stopped = planned.stopped.clone();
+        let mut stats = planned.stats.clone();
+        let mut stages = vec![StageResult {
+            name: planner_name.clone(),
+            result: planned,
+        }];
+
+        for (index, (name, optimizer)) in self.optimizers.iter().enumerate() {
+            let previous = &stages[stages.len() - 1].result.path;
+            let optimized = optimizer.optimize(problem, previous, &context.clone().with_stage(index + 1))?;
+            stopped = stopped.or_else(|| optimized.stopped.clone());
+            stats.merge(&optimized.stats);
+            stages.push(StageResult {
+                name: name.clone(),
+                result: optimized,
+            });
+        }
+
+        let path = stages[stages.len() - 1].result.path.clone();
+        Ok(PlanResult::new(problem, path, started.elapsed(), stats)
+            .with_stopped(stopped)
+            .with_stages(stages))
+    }
+}
@@ -1,24 +1,42 @@
-use crate::world::grid::*;
-use macroquad::prelude::*;
+use crate::algorithm::error::PlanError;
+use crate::world::geometry::Geometry;
+use crate::world::zone::ZoneCrossing;
+use glam::Vec2;
 use std::sync::Arc;
 
+/// Trade-off between path length and collision probability on uncertain occupancy.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskConfig {
+    /// Extra cost per unit of collision hazard `-ln(1 - p)`.
+    pub weight: f32,
+    /// Highest acceptable cumulative collision probability of a whole path, in `[0, 1)`.
+    pub bound: f32,
+}
+
 pub struct Problem {
-    pub grid_map: Arc<GridMap>,
+    pub world: Arc<dyn Geometry>,
     pub start: Vec2,
     pub goal: Vec2,
+    pub risk: Option<RiskConfig>,
 }
 
 impl Problem {
-    pub fn new(grid_map: Arc<GridMap>, start: Vec2, end: Vec2) -> Self {
+    pub fn new(world: Arc<dyn Geometry>, start: Vec2, end: Vec2) -> Self {
         Self {
-            grid_map,
+            world,
             start,
             goal: end,
+            risk: None,
         }
     }
 
-    pub fn grid_map(&self) -> &GridMap {
-        &self.grid_map
+    pub fn with_risk(mut self, risk: RiskConfig) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    pub fn world(&self) -> &dyn Geometry {
+        self.world.as_ref()
     }
 
     pub fn start(&self) -> Vec2 {
@@ -28,4 +46,72 @@ impl Problem {
     pub fn goal(&self) -> Vec2 {
         self.goal
     }
+
+    /// Checks that start and goal are finite points inside the world and outside obstacles.
+    pub fn validate(&self) -> Result<(), PlanError> {
+        if !self.start.is_finite() || !self.world.contains(self.start) {
+            return Err(PlanError::InvalidStart(self.start));
+        }
+        if !self.goal.is_finite() || !self.world.contains(self.goal) {
+            return Err(PlanError::InvalidGoal(self.goal));
+        }
+        if self.world.is_point_in_obstacle(self.start) {
+            return Err(PlanError::StartInObstacle(self.start));
+        }
+        if self.world.is_point_in_obstacle(self.goal) {
+            return Err(PlanError::GoalInObstacle(self.goal));
+        }
+        Ok(())
+    }
+
+    /// `false` if the segment enters a no-go zone.
+    pub fn is_segment_allowed(&self, from: Vec2, to: Vec2) -> bool {
+        self.world.zones().is_none_or(|zones| zones.is_segment_allowed(from, to))
+    }
+
+    /// Travel cost of a segment, its length scaled by the soft zones it crosses plus the weighted collision hazard.
+    pub fn segment_cost(&self, from: Vec2, to: Vec2) -> f32 {
+        let cost = match self.world.zones() {
+            Some(zones) => zones.segment_cost(from, to),
+            None => from.distance(to),
+        };
+
+        match self.risk {
+            Some(risk) => cost + risk.weight * self.world.segment_hazard(from, to) as f32,
+            None => cost,
+        }
+    }
+
+    /// Collision hazard planners accumulate against `hazard_budget`, zero unless a risk config is set.
+    pub fn segment_hazard(&self, from: Vec2, to: Vec2) -> f64 {
+        match self.risk {
+            Some(_) => self.world.segment_hazard(from, to),
+            None => 0.0,
+        }
+    }
+
+    /// Largest total hazard a path may accumulate while staying within the risk bound.
+    pub fn hazard_budget(&self) -> f64 {
+        self.risk
+            .map_or(f64::INFINITY, |risk| -(1.0 - risk.bound.clamp(0.0, 1.0) as f64).ln())
+    }
+
+    /// Probability that the path collides with an uncertain obstacle somewhere along its length.
+    pub fn collision_probability(&self, path: &[Vec2]) -> f64 {
+        let hazard: f64 = path.windows(2).map(|seg| self.world.segment_hazard(seg[0], seg[1])).sum();
+        1.0 - (-hazard).exp()
+    }
+
+    pub fn path_cost(&self, path: &[Vec2]) -> f32 {
+        path.windows(2).map(|seg| self.segment_cost(seg[0], seg[1])).sum()
+    }
+
+    /// Lower bound of the cost per unit of length anywhere in the world.
+    pub fn min_cost_factor(&self) -> f32 {
+        self.world.zones().map_or(1.0, |zones| zones.min_cost_factor())
+    }
+
+    pub fn crossed_zones(&self, path: &[Vec2]) -> Vec<ZoneCrossing> {
+        self.world.zones().map_or_else(Vec::new, |zones| zones.crossed_zones(path))
+    }
 }
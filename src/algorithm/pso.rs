@@ -1,63 +1,145 @@
+use crate::algorithm::context::PlanContext;
+use crate::algorithm::error::PlanError;
+use crate::algorithm::optimizer::PathOptimizer;
+use crate::algorithm::path_metrics::total_turn;
 use crate::algorithm::problem::Problem;
-use crate::world::types::Ray;
-use macroquad::{prelude::*, rand};
-
+use crate::algorithm::result::*;
+use crate::algorithm::validator::{PathValidator, certify};
+use crate::generator::seeded_rng;
+use crate::world::geometry::{CountingGeometry, Geometry};
+use glam::Vec2;
+use quad_rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+use std::time::Instant;
+
+/// Fitness of a particle that collides, enters a no-go zone or exceeds the hazard budget.
+const INFEASIBLE: f64 = 99999999.0;
+
+/// Particle swarm that refines the waypoints of an initial path, missing fields deserialize to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PsoStrategy {
+    /// Largest initial offset of a particle's waypoints from the initial path on each axis, 20.
     pub init_random_offset: f32,
+    /// Particles in the swarm, 100.
     pub swarms_count: usize,
+    /// Share of its previous velocity a particle keeps, 0.9.
     pub inertia_weight: f64,
+    /// Pull towards the particle's own best, 1.
     pub local_factor: f64,
+    /// Pull towards the swarm's best, 1.
     pub global_factor: f64,
+    /// Iterations, 100.
     pub iterate_count: usize,
+    /// Speed limit of a waypoint per iteration, 1000.
     pub max_velocity: f32,
+    /// Extra cost per radian of turning, so smoother paths win over slightly shorter ones. 0.
+    pub turn_weight: f64,
+    /// Seeds particle initialization and velocity updates, the same seed always gives the same path. 0.
+    pub seed: u64,
+}
+
+impl Default for PsoStrategy {
+    fn default() -> Self {
+        Self {
+            init_random_offset: 20.0,
+            swarms_count: 100,
+            inertia_weight: 0.9,
+            local_factor: 1.0,
+            global_factor: 1.0,
+            iterate_count: 100,
+            max_velocity: 1000.0,
+            turn_weight: 0.0,
+            seed: 0,
+        }
+    }
 }
 
 impl PsoStrategy {
-    pub fn upgrade_path(&self, problem: &Problem, init_path: &[Vec2]) -> Vec<Vec2> {
+    /// Refines `init_path`, a stop by the context's budgets returns the best particle so far.
+    pub fn upgrade_path(
+        &self,
+        problem: &Problem,
+        init_path: &[Vec2],
+        context: &PlanContext,
+    ) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        if init_path.len() < 2 {
+            return Err(PlanError::InvalidInitialPath);
+        }
+
+        let world = CountingGeometry::new(problem.world());
+        let mut stats = SearchStats {
+            // Particle positions, velocities and personal bests plus the global best
+            peak_memory_bytes: (3 * self.swarms_count + 1) * init_path.len() * size_of::<Vec2>(),
+            ..Default::default()
+        };
+        let seed = context.seed().unwrap_or(self.seed);
+        if self.swarms_count == 0 {
+            return certify(problem, PlanResult::new(problem, init_path.to_vec(), started.elapsed(), stats).with_seed(seed));
+        }
+        let rng = seeded_rng(seed);
+
         let mut swarms: Vec<Vec<Vec2>> = Vec::new();
         swarms.resize(self.swarms_count, Vec::new());
-        swarms.iter_mut().for_each(|x| *x = self.gen_init_particle(&init_path));
+        swarms.iter_mut().for_each(|x| *x = self.gen_init_particle(&rng, init_path));
 
         let mut swarms_velocity: Vec<Vec<Vec2>> = Vec::new();
         swarms_velocity.resize(self.swarms_count, Vec::new());
         swarms_velocity.iter_mut().for_each(|x| {
             x.resize(
                 init_path.len(),
-                Vec2::new(rand::gen_range(0.0, 50.0), rand::gen_range(0.0, 50.0)),
+                Vec2::new(rng.gen_range(0.0, 50.0), rng.gen_range(0.0, 50.0)),
             )
         });
 
         let mut best_particle_sol: Vec<Vec<Vec2>> = swarms.clone();
-        let mut best_particle_fitness: Vec<f64> = Vec::new();
-        best_particle_fitness.reserve(best_particle_sol.len());
+        let mut best_particle_fitness: Vec<f64> = Vec::with_capacity(best_particle_sol.len());
         for particle in best_particle_sol.iter() {
-            best_particle_fitness.push(self.cal_fitness(problem, particle));
+            best_particle_fitness.push(self.cal_fitness(problem, &world, particle));
         }
 
         let global_best_idx = best_particle_fitness
             .iter()
             .enumerate()
-            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-            .map(|(idx, _)| idx)
-            .unwrap();
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(idx, _)| idx);
         let mut global_best = best_particle_sol[global_best_idx].clone();
         let mut global_best_fitness = best_particle_fitness[global_best_idx];
+        // The input competes too, so the swarm never hands back anything worse than it was given
+        let init_fitness = self.cal_fitness(problem, &world, init_path);
+        if init_fitness < global_best_fitness {
+            global_best = init_path.to_vec();
+            global_best_fitness = init_fitness;
+        }
 
+        let mut stopped = None;
         for _ in 0..self.iterate_count {
+            if let Some(stop) = context.stop_reason(stats.iterations) {
+                if stop == PlanError::Cancelled {
+                    return Err(stop);
+                }
+                stopped = Some(stop);
+                break;
+            }
+            let mut improved = false;
             for index in 0..swarms.len() {
                 let particle = swarms.get_mut(index).unwrap();
                 let pre_velocity = swarms_velocity.get_mut(index).unwrap();
                 let local_best = &best_particle_sol[index];
 
-                let new_velocity = self.cal_velocity(particle, pre_velocity, local_best, &global_best);
+                let new_velocity = self.cal_velocity(&rng, particle, pre_velocity, local_best, &global_best);
 
                 *pre_velocity = new_velocity;
 
                 for i in 1..particle.len() - 1 {
-                    particle[i] = particle[i] + pre_velocity[i];
+                    particle[i] += pre_velocity[i];
                 }
+                context.emit(|observer| observer.particle_moved(index, particle));
 
-                let fitness = self.cal_fitness(problem, particle);
+                let fitness = self.cal_fitness(problem, &world, particle);
 
                 if fitness < best_particle_fitness[index] {
                     best_particle_fitness[index] = fitness;
@@ -66,15 +148,39 @@ impl PsoStrategy {
                     if fitness < global_best_fitness {
                         global_best_fitness = fitness;
                         global_best = particle.clone();
+                        context.emit(|observer| observer.new_best(&global_best, global_best_fitness));
+                        improved = true;
                     }
                 }
             }
+
+            stats.iterations += 1;
+            stats.best_per_iteration.push(global_best_fitness);
+
+            if improved {
+                stats.raycasts = world.raycasts();
+                stats.segment_checks = world.segment_checks();
+                context.report_improvement(|| {
+                    PlanResult::new(problem, global_best.clone(), started.elapsed(), stats.clone()).with_seed(seed)
+                });
+            }
         }
 
-        global_best
+        stats.raycasts = world.raycasts();
+        stats.segment_checks = world.segment_checks();
+        // Neither the input nor any particle was feasible, report why instead of returning a colliding path
+        if global_best_fitness >= INFEASIBLE {
+            PathValidator::default().validate(problem, &global_best)?;
+        }
+        certify(
+            problem,
+            PlanResult::new(problem, global_best, started.elapsed(), stats)
+                .with_seed(seed)
+                .with_stopped(stopped),
+        )
     }
 
-    fn gen_init_particle(&self, ref_path: &[Vec2]) -> Vec<Vec2> {
+    fn gen_init_particle(&self, rng: &RandGenerator, ref_path: &[Vec2]) -> Vec<Vec2> {
         let mut particle = Vec::new();
 
         if ref_path.is_empty() {
@@ -84,8 +190,8 @@ impl PsoStrategy {
         particle.push(ref_path[0]);
 
         for point in ref_path.iter().skip(1).take(ref_path.len() - 2) {
-            let random_offset_x = (rand::gen_range(0.0, 1.0) - 0.5) * 2.0 * self.init_random_offset;
-            let random_offset_y = (rand::gen_range(0.0, 1.0) - 0.5) * 2.0 * self.init_random_offset;
+            let random_offset_x = (rng.gen_range(0.0, 1.0) - 0.5) * 2.0 * self.init_random_offset;
+            let random_offset_y = (rng.gen_range(0.0, 1.0) - 0.5) * 2.0 * self.init_random_offset;
 
             let new_point = Vec2::new(point.x + random_offset_x, point.y + random_offset_y);
 
@@ -99,35 +205,49 @@ impl PsoStrategy {
         particle
     }
 
-    fn cal_fitness(&self, problem: &Problem, particle: &[Vec2]) -> f64 {
+    fn cal_fitness(&self, problem: &Problem, world: &dyn Geometry, particle: &[Vec2]) -> f64 {
         if particle.len() < 2 {
             return 1e10;
         }
 
+        if !particle.iter().all(|&pt| world.contains(pt)) {
+            return INFEASIBLE;
+        }
+
         let mut total_length = 0.0;
+        let mut total_hazard = 0.0;
 
         for i in 0..particle.len() - 1 {
             let start = particle[i];
             let end = particle[i + 1];
 
-            let ray = Ray {
-                root: start,
-                dir: end - start,
-            };
-
-            if let Some(_hit) = problem.grid_map.raycast(ray) {
-                return 99999999.0;
+            if !world.is_segment_clear(start, end, 0.0) || !problem.is_segment_allowed(start, end) {
+                return INFEASIBLE;
             }
 
-            total_length += (end - start).length() as f64;
+            total_length += problem.segment_cost(start, end) as f64;
+            total_hazard += problem.segment_hazard(start, end);
         }
 
+        if total_hazard > problem.hazard_budget() {
+            return INFEASIBLE;
+        }
+
+        if self.turn_weight > 0.0 {
+            total_length += self.turn_weight * total_turn(particle) as f64;
+        }
         total_length
     }
 
-    fn cal_velocity(&self, particle: &[Vec2], pre_velocity: &[Vec2], local_best: &[Vec2], global_best: &[Vec2]) -> Vec<Vec2> {
-        let mut res = Vec::new();
-        res.reserve(pre_velocity.len());
+    fn cal_velocity(
+        &self,
+        rng: &RandGenerator,
+        particle: &[Vec2],
+        pre_velocity: &[Vec2],
+        local_best: &[Vec2],
+        global_best: &[Vec2],
+    ) -> Vec<Vec2> {
+        let mut res = Vec::with_capacity(pre_velocity.len());
 
         for index in 0..pre_velocity.len() {
             if index == 0 || index == pre_velocity.len() - 1 {
@@ -140,8 +260,8 @@ impl PsoStrategy {
             let local_best_i = local_best[index];
             let global_best_i = global_best[index];
 
-            let r1 = rand::gen_range(0.0, 1.0);
-            let r2 = rand::gen_range(0.0, 1.0);
+            let r1 = rng.gen_range(0.0, 1.0);
+            let r2 = rng.gen_range(0.0, 1.0);
 
             let mut new_velocity = pre_velocity_i * self.inertia_weight as f32
                 + (local_best_i - particle_i) * (self.local_factor as f32 * r1)
@@ -158,3 +278,69 @@ impl PsoStrategy {
         res
     }
 }
+
+impl PathOptimizer for PsoStrategy {
+    fn optimize(&self, problem: &Problem, path: &[Vec2], context: &PlanContext) -> Result<PlanResult, PlanError> {
+        self.upgrade_path(problem, path, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::a_star::AStarStrategy;
+    use crate::algorithm::strategy::Strategy;
+    use crate::gridmaker;
+    use crate::world::WorldConfig;
+    use crate::world::grid::{GridMap, GridNodeValue};
+    use std::sync::Arc;
+
+    #[test]
+    fn same_seed_gives_the_same_path() {
+        let preset = gridmaker::preset("rooms", 36).unwrap();
+        let (start, goal) = preset.suggested_queries[0];
+        let problem = Problem::new(Arc::new(preset.grid_map), start, goal);
+        let init = AStarStrategy::default().path_finding(&problem, &PlanContext::new()).unwrap();
+        let pso = PsoStrategy {
+            swarms_count: 30,
+            iterate_count: 30,
+            seed: 5,
+            ..Default::default()
+        };
+
+        let first = pso.optimize(&problem, &init.path, &PlanContext::new()).unwrap();
+        let second = pso.optimize(&problem, &init.path, &PlanContext::new()).unwrap();
+        assert_eq!(first.path, second.path);
+        assert_eq!(first.seed, Some(5));
+        assert_eq!(second.seed, Some(5));
+        assert!(first.cost <= init.cost);
+    }
+
+    #[test]
+    fn keeps_particles_inside_walls_touching_the_edge() {
+        // 300 x 300 world, each wall leaves a single cell gap along the map edge so the route hugs both edges
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (30, 30),
+            cell_size: 10.0,
+        });
+        for y in 0..29 {
+            grid_map.grid_mut().set(10, y, GridNodeValue::Obstacle);
+        }
+        for y in 1..30 {
+            grid_map.grid_mut().set(20, y, GridNodeValue::Obstacle);
+        }
+        let problem = Problem::new(Arc::new(grid_map), Vec2::new(50.0, 50.0), Vec2::new(250.0, 50.0));
+        let init = AStarStrategy::default().path_finding(&problem, &PlanContext::new()).unwrap();
+        let outside = [Vec2::new(50.0, 50.0), Vec2::new(105.0, 301.0), Vec2::new(250.0, 50.0)];
+        assert_eq!(PsoStrategy::default().cal_fitness(&problem, problem.world(), &outside), INFEASIBLE);
+
+        for seed in 0..10 {
+            let pso = PsoStrategy {
+                seed,
+                ..Default::default()
+            };
+            let result = pso.optimize(&problem, &init.path, &PlanContext::new()).unwrap();
+            assert!(PathValidator::default().violations(&problem, &result.path).is_empty(), "seed {}", seed);
+        }
+    }
+}
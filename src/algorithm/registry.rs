@@ -0,0 +1,208 @@
+use crate::algorithm::a_star::AStarStrategy;
+use crate::algorithm::aco::AcoStrategy;
+use crate::algorithm::optimizer::*;
+use crate::algorithm::pipeline::Pipeline;
+use crate::algorithm::pso::PsoStrategy;
+use crate::algorithm::shortcut::ShortcutOptimizer;
+use crate::algorithm::smoothing::SmoothingOptimizer;
+use crate::algorithm::strategy::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Builds a strategy from its params, fields left out of the params take the strategy's defaults.
+pub type StrategyFactory = fn(&Value) -> Result<BoxedStrategy, serde_json::Error>;
+
+pub type OptimizerFactory = fn(&Value) -> Result<BoxedOptimizer, serde_json::Error>;
+
+/// Registry name plus params of one pipeline stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageSpec {
+    pub name: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Pipeline as config, e.g. `{"planner": {"name": "aco"}, "optimizers": [{"name": "pso", "params": {"seed": 7}}]}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineSpec {
+    pub planner: StageSpec,
+    #[serde(default)]
+    pub optimizers: Vec<StageSpec>,
+}
+
+/// Planner settings of the demo and command line tools, usually read from a JSON file, missing fields keep
+/// their defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlannerConfig {
+    pub aco: AcoStrategy,
+    pub pso: PsoStrategy,
+    /// Seed of every run, a fresh one per run when absent.
+    pub seed: Option<u64>,
+}
+
+impl PlannerConfig {
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> { serde_json::from_str(text) }
+
+    /// ACO planning refined by PSO, both tuned by this config.
+    pub fn pipeline_spec(&self) -> PipelineSpec {
+        PipelineSpec {
+            planner: StageSpec {
+                name: "aco".to_string(),
+                params: serde_json::to_value(&self.aco).expect("strategy configs serialize to JSON"),
+            },
+            optimizers: vec![StageSpec {
+                name: "pso".to_string(),
+                params: serde_json::to_value(&self.pso).expect("strategy configs serialize to JSON"),
+            }],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RegistryError {
+    UnknownStrategy(String),
+    UnknownOptimizer(String),
+    InvalidParams(String, serde_json::Error),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownStrategy(name) => write!(f, "unknown strategy '{}'", name),
+            RegistryError::UnknownOptimizer(name) => write!(f, "unknown optimizer '{}'", name),
+            RegistryError::InvalidParams(name, err) => write!(f, "invalid params for '{}': {}", name, err),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Strategies and optimizers by name, so tools and config files can pick and tune planners without code changes.
+/// Params are JSON values.
+pub struct StrategyRegistry {
+    factories: BTreeMap<String, StrategyFactory>,
+    optimizers: BTreeMap<String, OptimizerFactory>,
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register("a_star", build_from_params::<AStarStrategy>);
+        registry.register("aco", build_from_params::<AcoStrategy>);
+        registry.register_optimizer("pso", optimizer_from_params::<PsoStrategy>);
+        registry.register_optimizer("shortcut", optimizer_from_params::<ShortcutOptimizer>);
+        registry.register_optimizer("smooth", optimizer_from_params::<SmoothingOptimizer>);
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    /// Registry without any strategy or optimizer, `default` has the built-in ones.
+    pub fn new() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+            optimizers: BTreeMap::new(),
+        }
+    }
+
+    /// Adds or replaces the strategy called `name`.
+    pub fn register(&mut self, name: &str, factory: StrategyFactory) { self.factories.insert(name.to_string(), factory); }
+
+    pub fn names(&self) -> Vec<&str> { self.factories.keys().map(String::as_str).collect() }
+
+    pub fn contains(&self, name: &str) -> bool { self.factories.contains_key(name) }
+
+    /// `Value::Null` params give the strategy's defaults.
+    pub fn build(&self, name: &str, params: &Value) -> Result<BoxedStrategy, RegistryError> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownStrategy(name.to_string()))?;
+        factory(params).map_err(|err| RegistryError::InvalidParams(name.to_string(), err))
+    }
+
+    /// Adds or replaces the optimizer called `name`.
+    pub fn register_optimizer(&mut self, name: &str, factory: OptimizerFactory) {
+        self.optimizers.insert(name.to_string(), factory);
+    }
+
+    pub fn optimizer_names(&self) -> Vec<&str> { self.optimizers.keys().map(String::as_str).collect() }
+
+    /// `Value::Null` params give the optimizer's defaults.
+    pub fn build_optimizer(&self, name: &str, params: &Value) -> Result<BoxedOptimizer, RegistryError> {
+        let factory = self
+            .optimizers
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownOptimizer(name.to_string()))?;
+        factory(params).map_err(|err| RegistryError::InvalidParams(name.to_string(), err))
+    }
+
+    /// Stages are named after their registry entries.
+    pub fn build_pipeline(&self, spec: &PipelineSpec) -> Result<Pipeline, RegistryError> {
+        let planner = self.build(&spec.planner.name, &spec.planner.params)?;
+        spec.optimizers
+            .iter()
+            .try_fold(Pipeline::new(&spec.planner.name, planner), |pipeline, stage| {
+                Ok(pipeline.then(&stage.name, self.build_optimizer(&stage.name, &stage.params)?))
+            })
+    }
+}
+
+fn params_or_defaults<T: DeserializeOwned>(params: &Value) -> Result<T, serde_json::Error> {
+    match params {
+        Value::Null => serde_json::from_value(Value::Object(Default::default())),
+        params => T::deserialize(params),
+    }
+}
+
+/// Factory for any strategy whose config is the strategy itself.
+pub fn build_from_params<S>(params: &Value) -> Result<BoxedStrategy, serde_json::Error>
+where
+    S: Strategy + DeserializeOwned + 'static,
+{
+    Ok(Box::new(params_or_defaults::<S>(params)?))
+}
+
+/// Factory for any optimizer whose config is the optimizer itself.
+pub fn optimizer_from_params<O>(params: &Value) -> Result<BoxedOptimizer, serde_json::Error>
+where
+    O: PathOptimizer + DeserializeOwned + 'static,
+{
+    Ok(Box::new(params_or_defaults::<O>(params)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn planner_config_from_json_builds_its_pipeline() {
+        let config = PlannerConfig::from_json(
+            r#"{"aco": {"min_ant_count": 300, "beta": 4.0, "heuristic": "octile"}, "pso": {"iterate_count": 50}, "seed": 7}"#,
+        )
+        .unwrap();
+        assert_eq!(config.aco.min_ant_count, 300);
+        assert_eq!(config.pso.iterate_count, 50);
+        assert_eq!(config.seed, Some(7));
+
+        let pipeline = StrategyRegistry::default().build_pipeline(&config.pipeline_spec());
+        assert!(pipeline.is_ok());
+    }
+
+    #[test]
+    fn planner_config_rejects_unknown_fields() {
+        assert!(PlannerConfig::from_json(r#"{"aco": {"ants": 3}}"#).is_err());
+        assert!(PlannerConfig::from_json(r#"{"a_star": {}}"#).is_err());
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let registry = StrategyRegistry::default();
+        assert!(matches!(registry.build("dijkstra", &Value::Null), Err(RegistryError::UnknownStrategy(_))));
+        assert!(matches!(registry.build_optimizer("aco", &Value::Null), Err(RegistryError::UnknownOptimizer(_))));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::algorithm::error::PlanError;
+use crate::algorithm::path_metrics::PathMetrics;
+use crate::algorithm::problem::Problem;
+use crate::world::clearance::ClearanceField;
+use crate::world::zone::ZoneCrossing;
+use glam::Vec2;
+use std::time::Duration;
+
+/// Work a strategy did to produce its path, counters that do not apply to a strategy stay at zero.
+#[derive(Debug, Clone, Default)]
+pub struct SearchStats {
+    /// Nodes taken off the open list by graph searches.
+    pub nodes_expanded: usize,
+    pub ants_dispatched: usize,
+    pub iterations: usize,
+    pub raycasts: usize,
+    /// `is_segment_clear` queries, these sweep the whole segment instead of stopping at the first hit.
+    pub segment_checks: usize,
+    /// Rough estimate of the bytes held at once by the strategy's own search structures.
+    pub peak_memory_bytes: usize,
+    /// Best cost or fitness after every iteration, empty for strategies that do not iterate.
+    pub best_per_iteration: Vec<f64>,
+}
+
+impl SearchStats {
+    /// Adds the work of a later stage, memory peaks are not additive so the larger one is kept.
+    pub fn merge(&mut self, other: &SearchStats) {
+        self.nodes_expanded += other.nodes_expanded;
+        self.ants_dispatched += other.ants_dispatched;
+        self.iterations += other.iterations;
+        self.raycasts += other.raycasts;
+        self.segment_checks += other.segment_checks;
+        self.peak_memory_bytes = self.peak_memory_bytes.max(other.peak_memory_bytes);
+        self.best_per_iteration.extend_from_slice(&other.best_per_iteration);
+    }
+}
+
+/// Result of one stage of a `Pipeline`.
+#[derive(Debug, Clone)]
+pub struct StageResult {
+    pub name: String,
+    pub result: PlanResult,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanResult {
+    pub path: Vec<Vec2>,
+    pub length: f32,
+    /// Cost under the problem's zones and risk settings, equal to `length` without them.
+    pub cost: f32,
+    pub elapsed: Duration,
+    pub stats: SearchStats,
+    /// Zones the path enters, in the order of first entry.
+    pub crossed_zones: Vec<ZoneCrossing>,
+    /// Seed of randomized strategies, rerunning with it reproduces this result exactly.
+    pub seed: Option<u64>,
+    /// Budget that cut the search short, the path is then the best found so far instead of the final one.
+    pub stopped: Option<PlanError>,
+    /// Results of every stage in order when this is a pipeline's result, empty otherwise.
+    pub stages: Vec<StageResult>,
+}
+
+impl PlanResult {
+    pub fn new(problem: &Problem, path: Vec<Vec2>, elapsed: Duration, stats: SearchStats) -> Self {
+        Self {
+            length: path_length(&path),
+            cost: problem.path_cost(&path),
+            crossed_zones: problem.crossed_zones(&path),
+            path,
+            elapsed,
+            stats,
+            seed: None,
+            stopped: None,
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_stopped(mut self, stopped: Option<PlanError>) -> Self {
+        self.stopped = stopped;
+        self
+    }
+
+    pub fn with_stages(mut self, stages: Vec<StageResult>) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    /// Shape and clearance of the path, for reports that have the world's clearance field at hand.
+    pub fn metrics(&self, clearance: &ClearanceField, clearance_threshold: f32) -> PathMetrics {
+        PathMetrics::new(&self.path, clearance, clearance_threshold)
+    }
+
+    /// One line overview for logs and the demo UI.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "len {:.0} cost {:.0} {:.1}ms",
+            self.length,
+            self.cost,
+            self.elapsed.as_secs_f64() * 1000.0
+        );
+        if self.stats.nodes_expanded > 0 {
+            summary += &format!(" nodes {}", self.stats.nodes_expanded);
+        }
+        if self.stats.ants_dispatched > 0 {
+            summary += &format!(" ants {}", self.stats.ants_dispatched);
+        }
+        if self.stats.iterations > 0 {
+            summary += &format!(" iters {}", self.stats.iterations);
+        }
+        summary += &format!(" rays {}", self.stats.raycasts + self.stats.segment_checks);
+        if !self.crossed_zones.is_empty() {
+            let crossings: Vec<String> = self
+                .crossed_zones
+                .iter()
+                .map(|crossing| format!("{} {:.0}", crossing.name, crossing.length))
+                .collect();
+            summary += &format!(" zones {}", crossings.join(", "));
+        }
+        if let Some(seed) = self.seed {
+            summary += &format!(" seed {}", seed);
+        }
+        if self.stopped.is_some() {
+            summary += " (stopped early)";
+        }
+        summary
+    }
+}
+
+pub fn path_length(path: &[Vec2]) -> f32 { path.windows(2).map(|seg| seg[0].distance(seg[1])).sum() }
@@ -0,0 +1,222 @@
+use crate::algorithm::heuristic::*;
+use crate::algorithm::problem::*;
+use crate::world::geometry::{Geometry, clip_segment_to_box};
+use crate::world::types::*;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Graph a search strategy walks, mapping its nodes to world positions.
+///
+/// Successors must be collision free and outside no-go zones, strategies only add the path-dependent
+/// checks such as the hazard budget on top. Costs must be at least the problem's `segment_cost` lower bound
+/// for an admissible heuristic to stay admissible.
+pub trait SearchSpace: Send + Sync {
+    type Node: Clone + Eq + Hash;
+
+    /// Node a search starting or ending at `pos` uses, `None` if the space cannot represent it or reach it. Strategies
+    /// link `pos` to the node's position with a straight segment when the two differ, see `link_hop`.
+    fn locate(&self, problem: &Problem, pos: Vec2) -> Option<Self::Node>;
+
+    fn position(&self, node: &Self::Node) -> Vec2;
+
+    /// Appends every node reachable from `node` in one step together with its travel cost.
+    fn successors(&self, problem: &Problem, world: &dyn Geometry, node: &Self::Node, out: &mut Vec<(Self::Node, f32)>);
+
+    /// Estimated cost from `node` to `goal`, `heuristic`'s distance at the cheapest zone cost factor.
+    fn heuristic(&self, problem: &Problem, heuristic: &dyn Heuristic, node: &Self::Node, goal: Vec2) -> f32 {
+        heuristic.estimate(self.position(node), goal) * problem.min_cost_factor()
+    }
+
+    /// Cost of the final hop from `node` straight to `goal`, `None` if it is blocked or forbidden.
+    fn connect_goal(&self, problem: &Problem, world: &dyn Geometry, node: &Self::Node, goal: Vec2) -> Option<f32> {
+        link_hop(problem, world, self.position(node), goal).map(|(cost, _)| cost)
+    }
+}
+
+/// Cost and collision hazard of the straight hop linking a query point and a node, `None` if it is blocked or
+/// forbidden. Zero-length hops are free.
+pub fn link_hop(problem: &Problem, world: &dyn Geometry, from: Vec2, to: Vec2) -> Option<(f32, f64)> {
+    if from == to {
+        return Some((0.0, 0.0));
+    }
+    (world.is_segment_clear(from, to, 0.0) && problem.is_segment_allowed(from, to))
+        .then(|| (problem.segment_cost(from, to), problem.segment_hazard(from, to)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeDirection {
+    Right,
+    UpRight,
+    Up,
+    UpLeft,
+    Left,
+    DownLeft,
+    Down,
+    DownRight,
+}
+impl ProbeDirection {
+    pub fn iter() -> std::slice::Iter<'static, Self> {
+        static DIRECTIONS: [ProbeDirection; 8] = [
+            ProbeDirection::Right,
+            ProbeDirection::UpRight,
+            ProbeDirection::Up,
+            ProbeDirection::UpLeft,
+            ProbeDirection::Left,
+            ProbeDirection::DownLeft,
+            ProbeDirection::Down,
+            ProbeDirection::DownRight,
+        ];
+        DIRECTIONS.iter()
+    }
+}
+
+/// Continuous space that probes eight directions from every point, stopping probes short at obstacles.
+/// Nodes are exact positions, stored as their bit patterns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProbeSpace {
+    /// Probe length, diagonal probes reach `sqrt(2)` times further. Defaults to 25.
+    pub step_size: f32,
+}
+
+impl Default for ProbeSpace {
+    fn default() -> Self { Self { step_size: 25.0 } }
+}
+
+impl ProbeSpace {
+    fn get_new_pos(&self, root: Vec2, direction: ProbeDirection, world: &dyn Geometry) -> Vec2 {
+        let offset = match direction {
+            ProbeDirection::Right => Vec2::X,               // (1, 0)
+            ProbeDirection::UpRight => Vec2::X + Vec2::Y,   // (1, 1)
+            ProbeDirection::Up => Vec2::Y,                  // (0, 1)
+            ProbeDirection::UpLeft => -Vec2::X + Vec2::Y,   // (-1, 1)
+            ProbeDirection::Left => -Vec2::X,               // (-1, 0)
+            ProbeDirection::DownLeft => -Vec2::X - Vec2::Y, // (-1, -1)
+            ProbeDirection::Down => -Vec2::Y,               // (0, -1)
+            ProbeDirection::DownRight => Vec2::X - Vec2::Y, // (1, -1)
+        };
+
+        // Hit distances run along the normalized direction, the diagonal offsets are longer than one
+        let reach = offset.length() * self.step_size;
+        let ray = Ray { root, dir: offset };
+        let travel = world.raycast(ray).map_or(reach, |hit| hit.dist.min(reach));
+        let target = root + offset.normalize() * travel;
+        if world.contains(target) {
+            return target;
+        }
+
+        // Rays leaving the world report no hit, the probe stops at the world's edge instead
+        let bounds = world.bounds();
+        clip_segment_to_box(root, target, bounds.pos, bounds.pos + bounds.siz, true).map_or(root, |(_, t1)| {
+            root.lerp(target, t1).clamp(bounds.pos, bounds.pos + bounds.siz)
+        })
+    }
+}
+
+impl SearchSpace for ProbeSpace {
+    type Node = (u32, u32);
+
+    fn locate(&self, _problem: &Problem, pos: Vec2) -> Option<Self::Node> {
+        pos.is_finite().then(|| (pos.x.to_bits(), pos.y.to_bits()))
+    }
+
+    fn position(&self, node: &Self::Node) -> Vec2 { Vec2::new(f32::from_bits(node.0), f32::from_bits(node.1)) }
+
+    fn successors(&self, problem: &Problem, world: &dyn Geometry, node: &Self::Node, out: &mut Vec<(Self::Node, f32)>) {
+        let pos = self.position(node);
+        for dir in ProbeDirection::iter() {
+            let new_pos = self.get_new_pos(pos, *dir, world);
+            if problem.is_segment_allowed(pos, new_pos) {
+                out.push(((new_pos.x.to_bits(), new_pos.y.to_bits()), problem.segment_cost(pos, new_pos)));
+            }
+        }
+    }
+}
+
+/// Square lattice of points `node_dist` apart, offset by half a spacing from the origin.
+/// Neighbours are the four axis-aligned points, plus the diagonal ones if enabled, inside the world and in line of sight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LatticeSpace {
+    /// Spacing of the lattice, 50.
+    pub node_dist: f32,
+    /// Also link the four diagonal neighbours, off by default.
+    pub diagonal: bool,
+}
+
+impl Default for LatticeSpace {
+    fn default() -> Self {
+        Self {
+            node_dist: 50.0,
+            diagonal: false,
+        }
+    }
+}
+
+impl LatticeSpace {
+    /// Rings of lattice points `locate` searches around a query point before giving up.
+    const LOCATE_RINGS: i32 = 3;
+
+    pub fn new(node_dist: f32) -> Self {
+        Self {
+            node_dist,
+            ..Default::default()
+        }
+    }
+}
+
+impl SearchSpace for LatticeSpace {
+    type Node = (i32, i32);
+
+    /// Nearest lattice point inside the world that `pos` can reach in a straight line. The four surrounding points
+    /// are tried first, then the rings around them, `None` if nothing within `LOCATE_RINGS` rings is reachable.
+    fn locate(&self, problem: &Problem, pos: Vec2) -> Option<Self::Node> {
+        if !pos.is_finite() {
+            return None;
+        }
+
+        let cell = (pos - Vec2::splat(self.node_dist / 2.0)) / self.node_dist;
+        let (x, y) = (cell.x.floor() as i32, cell.y.floor() as i32);
+        for ring in 1..=Self::LOCATE_RINGS {
+            let span = -(ring - 1)..=ring;
+            let mut candidates: Vec<Self::Node> = span
+                .clone()
+                .flat_map(|dy| span.clone().map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| dx.max(dy) == ring || dx.min(dy) == 1 - ring)
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .collect();
+            candidates.sort_by(|a, b| self.position(a).distance(pos).total_cmp(&self.position(b).distance(pos)));
+            let reachable = candidates.into_iter().find(|node| {
+                let node_pos = self.position(node);
+                problem.world().contains(node_pos) && link_hop(problem, problem.world(), pos, node_pos).is_some()
+            });
+            if reachable.is_some() {
+                return reachable;
+            }
+        }
+        None
+    }
+
+    fn position(&self, node: &Self::Node) -> Vec2 {
+        Vec2::new(
+            node.0 as f32 * self.node_dist + self.node_dist / 2.0,
+            node.1 as f32 * self.node_dist + self.node_dist / 2.0,
+        )
+    }
+
+    fn successors(&self, problem: &Problem, world: &dyn Geometry, node: &Self::Node, out: &mut Vec<(Self::Node, f32)>) {
+        const AXIS: [(i32, i32); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
+        const DIAGONAL: [(i32, i32); 4] = [(1, -1), (-1, -1), (-1, 1), (1, 1)];
+
+        let pos = self.position(node);
+        let diagonal: &[(i32, i32)] = if self.diagonal { &DIAGONAL } else { &[] };
+        for &(dx, dy) in AXIS.iter().chain(diagonal) {
+            let next = (node.0 + dx, node.1 + dy);
+            let next_pos = self.position(&next);
+            if world.contains(next_pos) && world.has_line_of_sight(pos, next_pos) && problem.is_segment_allowed(pos, next_pos) {
+                out.push((next, problem.segment_cost(pos, next_pos)));
+            }
+        }
+    }
+}
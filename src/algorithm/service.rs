@@ -0,0 +1,584 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::registry::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+/// Handle of a submitted job, unique within its service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(u64);
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "job {}", self.0) }
+}
+
+/// Final answer of one job.
+#[derive(Debug, Clone)]
+pub struct JobResult {
+    pub id: JobId,
+    pub result: Result<PlanResult, PlanError>,
+}
+
+/// Where a job's result goes. Callbacks run on the worker thread that finished the job, or on the thread
+/// that cancelled it.
+pub enum Delivery {
+    Channel(mpsc::Sender<JobResult>),
+    Callback(Box<dyn FnOnce(JobResult) + Send>),
+}
+
+impl Delivery {
+    fn deliver(self, result: JobResult) {
+        match self {
+            // A dropped receiver means nobody waits for the result anymore
+            Delivery::Channel(sender) => {
+                let _ = sender.send(result);
+            }
+            Delivery::Callback(callback) => callback(result),
+        }
+    }
+}
+
+/// A problem to solve with the pipeline `spec` describes.
+pub struct PlanJob {
+    pub problem: Arc<Problem>,
+    pub spec: PipelineSpec,
+    /// Higher runs first, jobs of equal priority in submission order. 0 by default.
+    pub priority: i32,
+    /// Deadline of the run, the context's own one if absent. A job still queued when it passes fails with
+    /// `TimeBudgetExhausted` without running.
+    pub deadline: Option<Instant>,
+    /// Observer, improvement channel, budgets and seed of the run, its cancellation token is replaced by the service's.
+    pub context: PlanContext,
+}
+
+impl PlanJob {
+    pub fn new(problem: Arc<Problem>, spec: PipelineSpec) -> Self {
+        Self {
+            problem,
+            spec,
+            priority: 0,
+            deadline: None,
+            context: PlanContext::new(),
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_context(mut self, context: PlanContext) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+/// Everything that decides a job's answer, jobs with equal keys share one run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct JobKey {
+    world: usize,
+    start: [u32; 2],
+    goal: [u32; 2],
+    risk: Option<[u32; 2]>,
+    spec: String,
+    seed: Option<u64>,
+    max_expansions: Option<usize>,
+}
+
+impl JobKey {
+    fn new(job: &PlanJob) -> Self {
+        let problem = &job.problem;
+        Self {
+            // Same world instance, comparing geometry by content would cost more than planning twice
+            world: Arc::as_ptr(&problem.world) as *const () as usize,
+            start: [problem.start.x.to_bits(), problem.start.y.to_bits()],
+            goal: [problem.goal.x.to_bits(), problem.goal.y.to_bits()],
+            risk: problem.risk.map(|risk| [risk.weight.to_bits(), risk.bound.to_bits()]),
+            // JSON objects keep their keys sorted, equal specs give equal strings
+            spec: serde_json::to_string(&job.spec).unwrap_or_default(),
+            seed: job.context.seed(),
+            max_expansions: job.context.max_expansions(),
+        }
+    }
+}
+
+/// One planning run and every job waiting for its result.
+struct Run {
+    key: JobKey,
+    problem: Arc<Problem>,
+    /// Taken by the worker that starts the run, `None` once it is running.
+    strategy: Option<BoxedStrategy>,
+    priority: i32,
+    deadline: Option<Instant>,
+    context: PlanContext,
+    cancel: CancellationToken,
+    subscribers: Vec<(JobId, Delivery)>,
+}
+
+#[derive(Default)]
+struct Queue {
+    next_id: u64,
+    runs: HashMap<u64, Run>,
+    /// Runs not started yet, highest priority first and the oldest first among equals.
+    waiting: BTreeSet<(Reverse<i32>, u64)>,
+    by_key: HashMap<JobKey, u64>,
+    by_job: HashMap<JobId, u64>,
+    shutdown: bool,
+}
+
+impl Queue {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Queues `job`, or attaches it to an identical run in flight and raises that run's priority if it still waits.
+    fn enqueue(&mut self, job: PlanJob, strategy: BoxedStrategy, delivery: Delivery) -> JobId {
+        let id = JobId(self.next_id());
+        let key = JobKey::new(&job);
+
+        if let Some(&run_id) = self.by_key.get(&key) {
+            let run = self.runs.get_mut(&run_id).expect("keyed runs are registered");
+            if run.strategy.is_some() && job.priority > run.priority {
+                self.waiting.remove(&(Reverse(run.priority), run_id));
+                self.waiting.insert((Reverse(job.priority), run_id));
+                run.priority = job.priority;
+            }
+            run.subscribers.push((id, delivery));
+            self.by_job.insert(id, run_id);
+            return id;
+        }
+
+        let run_id = self.next_id();
+        self.by_key.insert(key.clone(), run_id);
+        self.by_job.insert(id, run_id);
+        self.waiting.insert((Reverse(job.priority), run_id));
+        self.runs.insert(
+            run_id,
+            Run {
+                key,
+                problem: job.problem,
+                strategy: Some(strategy),
+                priority: job.priority,
+                deadline: job.deadline.or(job.context.deadline()),
+                context: job.context,
+                cancel: CancellationToken::new(),
+                subscribers: vec![(id, delivery)],
+            },
+        );
+        id
+    }
+
+    /// Takes job `id` off its run, dropping a waiting run or cancelling a running one once nobody waits for it.
+    fn detach(&mut self, id: JobId) -> Option<Delivery> {
+        let run_id = self.by_job.remove(&id)?;
+        let run = self.runs.get_mut(&run_id)?;
+        let index = run.subscribers.iter().position(|(job, _)| *job == id)?;
+        let (_, delivery) = run.subscribers.remove(index);
+
+        if run.subscribers.is_empty() {
+            // New identical jobs must start their own run instead of joining this one
+            self.by_key.remove(&run.key);
+            if run.strategy.is_some() {
+                self.waiting.remove(&(Reverse(run.priority), run_id));
+                self.runs.remove(&run_id);
+            } else {
+                run.cancel.cancel();
+            }
+        }
+        Some(delivery)
+    }
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    wakeup: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> { self.queue.lock().unwrap() }
+}
+
+/// Long-lived planning service, jobs are planned on a fixed pool of worker threads in priority order.
+///
+/// Every submitted job gets exactly one result through its delivery, unless it is replaced. A panicking strategy
+/// fails its jobs with `Err(Panicked)` and leaves its worker running.
+///
+/// A job identical to one in flight, same world instance, start, goal, risk, spec, seed and expansion budget,
+/// shares that job's run instead of planning again. The shared run keeps the first job's context and deadline.
+pub struct PlannerService {
+    registry: StrategyRegistry,
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl PlannerService {
+    /// Starts `workers` threads, 0 uses every available core.
+    pub fn new(workers: usize, registry: StrategyRegistry) -> Self {
+        let workers = match workers {
+            0 => thread::available_parallelism().map_or(1, |count| count.get()),
+            workers => workers,
+        };
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue::default()),
+            wakeup: Condvar::new(),
+        });
+        let workers = (0..workers)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || work(&shared))
+            })
+            .collect();
+
+        Self {
+            registry,
+            shared,
+            workers,
+        }
+    }
+
+    pub fn registry(&self) -> &StrategyRegistry { &self.registry }
+
+    /// Fails right away if the registry cannot build the job's pipeline.
+    pub fn submit(&self, job: PlanJob, delivery: Delivery) -> Result<JobId, RegistryError> {
+        let strategy: BoxedStrategy = Box::new(self.registry.build_pipeline(&job.spec)?);
+        let id = self.shared.lock().enqueue(job, strategy, delivery);
+        self.shared.wakeup.notify_one();
+        Ok(id)
+    }
+
+    /// Stops job `id`, its delivery gets `Err(Cancelled)`. `false` if the job already finished or never existed.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let delivery = self.shared.lock().detach(id);
+        match delivery {
+            Some(delivery) => {
+                delivery.deliver(JobResult {
+                    id,
+                    result: Err(PlanError::Cancelled),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops job `id` without delivering anything for it and submits `job` in its place, e.g. when the goal moved.
+    /// If `job` cannot be built, job `id` keeps running.
+    pub fn replace(&self, id: JobId, job: PlanJob, delivery: Delivery) -> Result<JobId, RegistryError> {
+        let strategy: BoxedStrategy = Box::new(self.registry.build_pipeline(&job.spec)?);
+        let new_id = {
+            let mut queue = self.shared.lock();
+            queue.detach(id);
+            queue.enqueue(job, strategy, delivery)
+        };
+        self.shared.wakeup.notify_one();
+        Ok(new_id)
+    }
+}
+
+impl Drop for PlannerService {
+    /// Cancels every job in flight and waits for the workers, jobs still queued get `Err(Cancelled)`.
+    fn drop(&mut self) {
+        let abandoned: Vec<(JobId, Delivery)> = {
+            let mut queue = self.shared.lock();
+            queue.shutdown = true;
+            queue.runs.values().for_each(|run| run.cancel.cancel());
+            let waiting = std::mem::take(&mut queue.waiting);
+            waiting
+                .into_iter()
+                .filter_map(|(_, run_id)| queue.runs.remove(&run_id))
+                .flat_map(|run| run.subscribers)
+                .collect()
+        };
+        self.shared.wakeup.notify_all();
+
+        for (id, delivery) in abandoned {
+            delivery.deliver(JobResult {
+                id,
+                result: Err(PlanError::Cancelled),
+            });
+        }
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work(shared: &Shared) {
+    let mut queue = shared.lock();
+    loop {
+        if queue.shutdown {
+            return;
+        }
+        let Some((_, run_id)) = queue.waiting.pop_first() else {
+            queue = shared.wakeup.wait(queue).unwrap();
+            continue;
+        };
+
+        let run = queue.runs.get_mut(&run_id).expect("waiting runs are registered");
+        let strategy = run.strategy.take().expect("waiting runs hold their strategy");
+        let problem = Arc::clone(&run.problem);
+        let deadline = run.deadline;
+        let mut context = run.context.clone().with_cancellation(run.cancel.clone());
+        if let Some(deadline) = deadline {
+            context = context.with_deadline(deadline);
+        }
+        drop(queue);
+
+        let result = if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+            Err(PlanError::TimeBudgetExhausted)
+        } else {
+            panic::catch_unwind(AssertUnwindSafe(|| strategy.path_finding(&problem, &context)))
+                .unwrap_or_else(|payload| Err(PlanError::Panicked(panic_message(payload.as_ref()))))
+        };
+
+        queue = shared.lock();
+        let run = queue.runs.remove(&run_id).expect("running runs stay registered");
+        if queue.by_key.get(&run.key) == Some(&run_id) {
+            queue.by_key.remove(&run.key);
+        }
+        for (id, _) in &run.subscribers {
+            queue.by_job.remove(id);
+        }
+        drop(queue);
+
+        for (id, delivery) in run.subscribers {
+            let result = JobResult {
+                id,
+                result: result.clone(),
+            };
+            // A panicking callback must neither stop the other deliveries nor shrink the pool
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| delivery.deliver(result)));
+        }
+        queue = shared.lock();
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+    use crate::world::geometry::Geometry;
+    use crate::world::grid::GridMap;
+    use glam::Vec2;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Holds its worker until the run is cancelled.
+    struct Blocking;
+
+    impl Strategy for Blocking {
+        fn path_finding(&self, _problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+            loop {
+                context.check(0)?;
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    /// Straight line from start to goal.
+    struct Straight;
+
+    impl Strategy for Straight {
+        fn path_finding(&self, problem: &Problem, _context: &PlanContext) -> Result<PlanResult, PlanError> {
+            let path = vec![problem.start(), problem.goal()];
+            Ok(PlanResult::new(problem, path, Duration::ZERO, SearchStats::default()))
+        }
+    }
+
+    static COUNTED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Straight line that counts its runs, only the dedup test uses it.
+    struct Counted;
+
+    impl Strategy for Counted {
+        fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+            COUNTED_RUNS.fetch_add(1, Ordering::SeqCst);
+            Straight.path_finding(problem, context)
+        }
+    }
+
+    struct Panicking;
+
+    impl Strategy for Panicking {
+        fn path_finding(&self, _problem: &Problem, _context: &PlanContext) -> Result<PlanResult, PlanError> {
+            panic!("strategy bug")
+        }
+    }
+
+    fn service(workers: usize) -> PlannerService {
+        let mut registry = StrategyRegistry::new();
+        registry.register("blocking", |_| Ok(Box::new(Blocking)));
+        registry.register("straight", |_| Ok(Box::new(Straight)));
+        registry.register("counted", |_| Ok(Box::new(Counted)));
+        registry.register("panicking", |_| Ok(Box::new(Panicking)));
+        PlannerService::new(workers, registry)
+    }
+
+    fn world() -> Arc<dyn Geometry> {
+        Arc::new(GridMap::new(WorldConfig {
+            grid_size: (10, 10),
+            cell_size: 10.0,
+        }))
+    }
+
+    fn job(world: &Arc<dyn Geometry>, strategy: &str, goal_x: f32) -> PlanJob {
+        let problem = Problem::new(Arc::clone(world), Vec2::new(5.0, 5.0), Vec2::new(goal_x, 95.0));
+        let spec = PipelineSpec {
+            planner: StageSpec {
+                name: strategy.to_string(),
+                params: Value::Null,
+            },
+            optimizers: Vec::new(),
+        };
+        PlanJob::new(Arc::new(problem), spec)
+    }
+
+    #[test]
+    fn waiting_jobs_run_by_priority_then_submission_order() {
+        let service = service(1);
+        let world = world();
+        let (sender, receiver) = mpsc::channel();
+        // Whether or not the worker took it yet, the blocker runs before anything submitted below
+        let blocker = service
+            .submit(job(&world, "blocking", 95.0).with_priority(i32::MAX), Delivery::Channel(sender.clone()))
+            .unwrap();
+
+        let low = service
+            .submit(job(&world, "straight", 10.0).with_priority(0), Delivery::Channel(sender.clone()))
+            .unwrap();
+        let high = service
+            .submit(job(&world, "straight", 20.0).with_priority(5), Delivery::Channel(sender.clone()))
+            .unwrap();
+        let also_low = service
+            .submit(job(&world, "straight", 30.0).with_priority(0), Delivery::Channel(sender.clone()))
+            .unwrap();
+        assert!(service.cancel(blocker));
+
+        // The blocker's cancellation is delivered by this thread and may race the first run
+        let order: Vec<JobId> = (0..4)
+            .map(|_| receiver.recv_timeout(TIMEOUT).unwrap().id)
+            .filter(|&id| id != blocker)
+            .collect();
+        assert_eq!(order, vec![high, low, also_low]);
+    }
+
+    #[test]
+    fn identical_jobs_share_one_run() {
+        let service = service(1);
+        let world = world();
+        let (sender, receiver) = mpsc::channel();
+        let blocker = service
+            .submit(job(&world, "blocking", 95.0).with_priority(i32::MAX), Delivery::Channel(sender.clone()))
+            .unwrap();
+
+        let first = job(&world, "counted", 50.0);
+        let twin = PlanJob::new(Arc::clone(&first.problem), first.spec.clone());
+        service.submit(first, Delivery::Channel(sender.clone())).unwrap();
+        service.submit(twin, Delivery::Channel(sender.clone())).unwrap();
+        service.submit(job(&world, "counted", 60.0), Delivery::Channel(sender.clone())).unwrap();
+        service.cancel(blocker);
+
+        let results: Vec<JobResult> = (0..4).map(|_| receiver.recv_timeout(TIMEOUT).unwrap()).collect();
+        assert!(results.iter().filter(|result| result.id != blocker).all(|result| result.result.is_ok()));
+        assert_eq!(COUNTED_RUNS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn cancelled_jobs_get_cancelled_once() {
+        let service = service(1);
+        let world = world();
+        let (sender, receiver) = mpsc::channel();
+        let running = service.submit(job(&world, "blocking", 95.0), Delivery::Channel(sender.clone())).unwrap();
+        let queued = service.submit(job(&world, "straight", 10.0), Delivery::Channel(sender.clone())).unwrap();
+
+        assert!(service.cancel(queued));
+        assert!(!service.cancel(queued));
+        assert!(service.cancel(running));
+        drop(sender);
+
+        let results: Vec<JobResult> = receiver.iter().take(2).collect();
+        assert_eq!(results.iter().map(|result| result.id).collect::<Vec<_>>(), vec![queued, running]);
+        assert!(results.iter().all(|result| matches!(result.result, Err(PlanError::Cancelled))));
+        drop(service);
+        assert!(receiver.recv_timeout(TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn replaced_jobs_deliver_nothing() {
+        let service = service(1);
+        let world = world();
+        let (blocker_sender, blocker_receiver) = mpsc::channel();
+        let blocker = service.submit(job(&world, "blocking", 95.0), Delivery::Channel(blocker_sender)).unwrap();
+
+        let (old_sender, old_receiver) = mpsc::channel();
+        let old = service.submit(job(&world, "straight", 10.0), Delivery::Channel(old_sender)).unwrap();
+        let (new_sender, new_receiver) = mpsc::channel();
+        let new = service
+            .replace(old, job(&world, "straight", 20.0), Delivery::Channel(new_sender))
+            .unwrap();
+        service.cancel(blocker);
+        blocker_receiver.recv_timeout(TIMEOUT).unwrap();
+
+        let result = new_receiver.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(result.id, new);
+        assert_eq!(result.result.unwrap().path.last(), Some(&Vec2::new(20.0, 95.0)));
+        // The replaced job's delivery was dropped unused
+        assert!(old_receiver.recv_timeout(TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn dropping_the_service_cancels_running_and_queued_jobs() {
+        let service = service(1);
+        let world = world();
+        let (sender, receiver) = mpsc::channel();
+        let running = service.submit(job(&world, "blocking", 95.0), Delivery::Channel(sender.clone())).unwrap();
+        let queued = service.submit(job(&world, "straight", 10.0), Delivery::Channel(sender)).unwrap();
+        drop(service);
+
+        let mut results: Vec<JobResult> = receiver.iter().collect();
+        results.sort_by_key(|result| result.id.0);
+        assert_eq!(results.iter().map(|result| result.id).collect::<Vec<_>>(), vec![running, queued]);
+        assert!(results.iter().all(|result| matches!(result.result, Err(PlanError::Cancelled))));
+    }
+
+    #[test]
+    fn panicking_strategies_fail_their_job_and_keep_the_worker() {
+        let service = service(1);
+        let world = world();
+        let (sender, receiver) = mpsc::channel();
+        service.submit(job(&world, "panicking", 95.0), Delivery::Channel(sender.clone())).unwrap();
+        service.submit(job(&world, "straight", 10.0), Delivery::Channel(sender)).unwrap();
+
+        let failed = receiver.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(failed.result.unwrap_err(), PlanError::Panicked("strategy bug".to_string()));
+        assert!(receiver.recv_timeout(TIMEOUT).unwrap().result.is_ok());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::optimizer::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::{CountingGeometry, Geometry};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Greedy shortcutting, every waypoint jumps to the furthest later waypoint it can reach directly.
+///
+/// A shortcut is only taken if it is clear, stays out of no-go zones and costs no more, in both zone cost
+/// and collision hazard, than the part of the path it replaces. The result is never worse than the input.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShortcutOptimizer {
+    /// Clearance a shortcut keeps from obstacles, 0.
+    pub radius: f32,
+}
+
+impl ShortcutOptimizer {
+    fn can_shortcut(&self, problem: &Problem, world: &dyn Geometry, path: &[Vec2]) -> bool {
+        let (from, to) = (path[0], path[path.len() - 1]);
+        if !world.is_segment_clear(from, to, self.radius) || !problem.is_segment_allowed(from, to) {
+            return false;
+        }
+
+        let replaced_hazard: f64 = path.windows(2).map(|seg| problem.segment_hazard(seg[0], seg[1])).sum();
+        problem.segment_cost(from, to) <= problem.path_cost(path) && problem.segment_hazard(from, to) <= replaced_hazard
+    }
+}
+
+impl PathOptimizer for ShortcutOptimizer {
+    fn optimize(&self, problem: &Problem, path: &[Vec2], context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        if path.len() < 2 {
+            return Err(PlanError::InvalidInitialPath);
+        }
+
+        let world = CountingGeometry::new(problem.world());
+        let mut stats = SearchStats::default();
+        let mut shortened = vec![path[0]];
+        let mut index = 0;
+        let mut stopped = None;
+        while index < path.len() - 1 {
+            if let Some(stop) = context.stop_reason(stats.iterations) {
+                if stop == PlanError::Cancelled {
+                    return Err(stop);
+                }
+                // The rest of the input is still a valid way to the goal
+                shortened.extend_from_slice(&path[index + 1..]);
+                stopped = Some(stop);
+                break;
+            }
+
+            // Furthest first, the direct neighbour is the fallback that keeps the input as is
+            let next = (index + 2..path.len())
+                .rev()
+                .find(|&later| self.can_shortcut(problem, &world, &path[index..=later]))
+                .unwrap_or(index + 1);
+            shortened.push(path[next]);
+            index = next;
+            stats.iterations += 1;
+        }
+
+        stats.raycasts = world.raycasts();
+        stats.segment_checks = world.segment_checks();
+        let result = certify(problem, PlanResult::new(problem, shortened, started.elapsed(), stats).with_stopped(stopped))?;
+        context.report_improvement(|| result.clone());
+        Ok(result)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::optimizer::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::{CountingGeometry, Geometry};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Chaikin corner cutting, each pass replaces a corner by two points `ratio` of the way along its legs.
+///
+/// A corner is only cut if the new segment is clear, stays out of no-go zones and costs no more, in both
+/// zone cost and collision hazard, than the corner it replaces, so the result is never worse than the input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmoothingOptimizer {
+    /// Corner cutting passes, every pass doubles the waypoints around cut corners. 2.
+    pub iterations: usize,
+    /// Share of each leg cut off a corner, clamped to `(0, 0.5]`. 0.25.
+    pub ratio: f32,
+    /// Clearance a cut keeps from obstacles, 0.
+    pub radius: f32,
+}
+
+impl Default for SmoothingOptimizer {
+    fn default() -> Self {
+        Self {
+            iterations: 2,
+            ratio: 0.25,
+            radius: 0.0,
+        }
+    }
+}
+
+impl SmoothingOptimizer {
+    fn smooth_pass(&self, problem: &Problem, world: &dyn Geometry, path: &[Vec2]) -> Vec<Vec2> {
+        let ratio = self.ratio.clamp(f32::EPSILON, 0.5);
+        let mut smoothed = Vec::with_capacity(path.len() * 2);
+        smoothed.push(path[0]);
+
+        for corner in path.windows(3) {
+            let (before, at, after) = (corner[0], corner[1], corner[2]);
+            let cut_from = at + (before - at) * ratio;
+            let cut_to = at + (after - at) * ratio;
+
+            let cheaper = problem.segment_cost(cut_from, cut_to)
+                <= problem.segment_cost(cut_from, at) + problem.segment_cost(at, cut_to)
+                && problem.segment_hazard(cut_from, cut_to)
+                    <= problem.segment_hazard(cut_from, at) + problem.segment_hazard(at, cut_to);
+            if cheaper
+                && world.is_segment_clear(cut_from, cut_to, self.radius)
+                && problem.is_segment_allowed(cut_from, cut_to)
+            {
+                smoothed.push(cut_from);
+                smoothed.push(cut_to);
+            } else {
+                smoothed.push(at);
+            }
+        }
+
+        smoothed.push(path[path.len() - 1]);
+        smoothed
+    }
+}
+
+impl PathOptimizer for SmoothingOptimizer {
+    fn optimize(&self, problem: &Problem, path: &[Vec2], context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        if path.len() < 2 {
+            return Err(PlanError::InvalidInitialPath);
+        }
+
+        let world = CountingGeometry::new(problem.world());
+        let mut stats = SearchStats::default();
+        let mut smoothed = path.to_vec();
+        let mut stopped = None;
+        for _ in 0..self.iterations {
+            if let Some(stop) = context.stop_reason(stats.iterations) {
+                if stop == PlanError::Cancelled {
+                    return Err(stop);
+                }
+                stopped = Some(stop);
+                break;
+            }
+            smoothed = self.smooth_pass(problem, &world, &smoothed);
+            stats.iterations += 1;
+        }
+
+        stats.raycasts = world.raycasts();
+        stats.segment_checks = world.segment_checks();
+        let result = certify(problem, PlanResult::new(problem, smoothed, started.elapsed(), stats).with_stopped(stopped))?;
+        context.report_improvement(|| result.clone());
+        Ok(result)
+    }
+}
@@ -1,6 +1,11 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
 use crate::algorithm::problem::*;
-use macroquad::prelude::*;
+use crate::algorithm::result::*;
 
-pub trait Strategy {
-    fn path_finding(&self, problem: &Problem) -> Option<Vec<Vec2>>;
+pub type BoxedStrategy = Box<dyn Strategy>;
+
+/// Plans a path for a problem, strategies are shared across threads by batch and anytime planning.
+pub trait Strategy: Send + Sync {
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError>;
 }
@@ -0,0 +1,346 @@
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One way a path fails the problem it claims to solve, segments are numbered by their first waypoint.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathViolation {
+    /// The path has fewer than two waypoints, holds their count.
+    TooShort(usize),
+    NonFinite { index: usize, point: Vec2 },
+    OutOfBounds { index: usize, point: Vec2 },
+    StartMismatch { expected: Vec2, actual: Vec2 },
+    GoalMismatch { expected: Vec2, actual: Vec2 },
+    /// The segment, widened by the agent radius, overlaps an obstacle.
+    Collision { segment: usize, from: Vec2, to: Vec2 },
+    NoGoZone { segment: usize, from: Vec2, to: Vec2 },
+    HazardBudget { hazard: f64, budget: f64 },
+}
+
+impl fmt::Display for PathViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathViolation::TooShort(len) => write!(f, "path has {} waypoints, needs at least two", len),
+            PathViolation::NonFinite { index, point } => write!(f, "waypoint {} {} is not finite", index, point),
+            PathViolation::OutOfBounds { index, point } => write!(f, "waypoint {} {} is outside the world", index, point),
+            PathViolation::StartMismatch { expected, actual } => {
+                write!(f, "path starts at {} instead of {}", actual, expected)
+            }
+            PathViolation::GoalMismatch { expected, actual } => write!(f, "path ends at {} instead of {}", actual, expected),
+            PathViolation::Collision { segment, from, to } => {
+                write!(f, "segment {} from {} to {} hits an obstacle", segment, from, to)
+            }
+            PathViolation::NoGoZone { segment, from, to } => {
+                write!(f, "segment {} from {} to {} enters a no-go zone", segment, from, to)
+            }
+            PathViolation::HazardBudget { hazard, budget } => {
+                write!(f, "collision hazard {:.3} exceeds the budget {:.3}", hazard, budget)
+            }
+        }
+    }
+}
+
+/// Certifies that a path solves its problem: it runs from the start to the goal inside the world, every
+/// segment is clear of obstacles and no-go zones and the whole path stays within the hazard budget.
+///
+/// Segments are checked with the world's `is_segment_clear`, which `GridMap` answers against its cells rather
+/// than by sampling. A segment may graze an obstacle's edge, but squeezing through the corner two diagonally
+/// touching obstacle cells share counts as a collision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathValidator {
+    /// Radius of the agent, every segment keeps at least this far from obstacles. 0.
+    pub radius: f32,
+    /// Largest distance allowed between the path's ends and the problem's start and goal, 0.001.
+    pub endpoint_tolerance: f32,
+}
+
+impl Default for PathValidator {
+    fn default() -> Self {
+        Self {
+            radius: 0.0,
+            endpoint_tolerance: 1e-3,
+        }
+    }
+}
+
+impl PathValidator {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            ..Default::default()
+        }
+    }
+
+    /// Every violation of `path`, empty for a valid one.
+    pub fn violations(&self, problem: &Problem, path: &[Vec2]) -> Vec<PathViolation> {
+        let mut violations = Vec::new();
+        if path.len() < 2 {
+            violations.push(PathViolation::TooShort(path.len()));
+        }
+        let (Some(&first), Some(&last)) = (path.first(), path.last()) else {
+            return violations;
+        };
+
+        if first.distance(problem.start()) > self.endpoint_tolerance || !first.is_finite() {
+            violations.push(PathViolation::StartMismatch {
+                expected: problem.start(),
+                actual: first,
+            });
+        }
+        if last.distance(problem.goal()) > self.endpoint_tolerance || !last.is_finite() {
+            violations.push(PathViolation::GoalMismatch {
+                expected: problem.goal(),
+                actual: last,
+            });
+        }
+
+        for (index, &point) in path.iter().enumerate() {
+            if !point.is_finite() {
+                violations.push(PathViolation::NonFinite { index, point });
+            } else if !problem.world().contains(point) {
+                violations.push(PathViolation::OutOfBounds { index, point });
+            }
+        }
+
+        for (segment, seg) in path.windows(2).enumerate() {
+            let (from, to) = (seg[0], seg[1]);
+            if !from.is_finite() || !to.is_finite() {
+                continue;
+            }
+            if !problem.world().is_segment_clear(from, to, self.radius) {
+                violations.push(PathViolation::Collision { segment, from, to });
+            }
+            if !problem.is_segment_allowed(from, to) {
+                violations.push(PathViolation::NoGoZone { segment, from, to });
+            }
+        }
+
+        let budget = problem.hazard_budget();
+        if budget.is_finite() {
+            let hazard: f64 = path.windows(2).map(|seg| problem.segment_hazard(seg[0], seg[1])).sum();
+            if hazard > budget {
+                violations.push(PathViolation::HazardBudget { hazard, budget });
+            }
+        }
+
+        violations
+    }
+
+    pub fn validate(&self, problem: &Problem, path: &[Vec2]) -> Result<(), PlanError> {
+        let violations = self.violations(problem, path);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PlanError::InvalidPath(violations))
+        }
+    }
+}
+
+/// Runs the default validator on a strategy's final result so no build hands out a path that breaks the problem.
+pub fn certify(problem: &Problem, result: PlanResult) -> Result<PlanResult, PlanError> {
+    PathValidator::default().validate(problem, &result.path)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::a_star::AStarStrategy;
+    use crate::algorithm::aco::AcoStrategy;
+    use crate::algorithm::context::PlanContext;
+    use crate::algorithm::medial_axis::MedialAxisStrategy;
+    use crate::algorithm::navmesh::NavMeshStrategy;
+    use crate::algorithm::optimizer::PathOptimizer;
+    use crate::algorithm::pso::PsoStrategy;
+    use crate::algorithm::shortcut::ShortcutOptimizer;
+    use crate::algorithm::smoothing::SmoothingOptimizer;
+    use crate::algorithm::strategy::Strategy;
+    use crate::algorithm::visibility::VisibilityGraphStrategy;
+    use crate::gridmaker;
+    use crate::world::WorldConfig;
+    use crate::world::geometry::Geometry;
+    use crate::world::grid::{GridMap, GridNodeValue};
+    use crate::world::medial_axis::{MedialAxisConfig, MedialAxisRoadmap};
+    use crate::world::navmesh::NavMesh;
+    use crate::world::occupancy::OccupancyConfig;
+    use crate::world::types::quad;
+    use crate::world::visibility::VisibilityGraph;
+    use crate::world::zone::{Zone, ZoneKind};
+    use std::sync::Arc;
+
+    /// An empty 100 x 100 world of 10 unit cells.
+    fn empty_map() -> GridMap {
+        GridMap::new(WorldConfig {
+            grid_size: (10, 10),
+            cell_size: 10.0,
+        })
+    }
+
+    fn violations(grid_map: GridMap, path: &[Vec2]) -> Vec<PathViolation> {
+        let problem = Problem::new(Arc::new(grid_map), path[0], path[path.len() - 1]);
+        PathValidator::default().violations(&problem, path)
+    }
+
+    #[test]
+    fn accepts_a_clear_path() {
+        let path = [Vec2::new(5.0, 5.0), Vec2::new(50.0, 5.0), Vec2::new(95.0, 95.0)];
+        assert!(violations(empty_map(), &path).is_empty());
+    }
+
+    #[test]
+    fn rejects_a_path_without_two_waypoints() {
+        let path = [Vec2::new(5.0, 5.0)];
+        assert_eq!(violations(empty_map(), &path), vec![PathViolation::TooShort(1)]);
+    }
+
+    #[test]
+    fn rejects_ends_away_from_the_start_and_goal() {
+        let problem = Problem::new(Arc::new(empty_map()), Vec2::new(5.0, 5.0), Vec2::new(95.0, 95.0));
+        let path = [Vec2::new(6.0, 5.0), Vec2::new(95.0, 94.0)];
+        assert_eq!(
+            PathValidator::default().violations(&problem, &path),
+            vec![
+                PathViolation::StartMismatch {
+                    expected: Vec2::new(5.0, 5.0),
+                    actual: Vec2::new(6.0, 5.0),
+                },
+                PathViolation::GoalMismatch {
+                    expected: Vec2::new(95.0, 95.0),
+                    actual: Vec2::new(95.0, 94.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_waypoints_outside_the_world() {
+        let path = [Vec2::new(5.0, 5.0), Vec2::new(5.0, 150.0), Vec2::new(5.0, 95.0)];
+        assert!(violations(empty_map(), &path).contains(&PathViolation::OutOfBounds {
+            index: 1,
+            point: Vec2::new(5.0, 150.0),
+        }));
+    }
+
+    #[test]
+    fn rejects_segments_through_obstacles() {
+        let mut grid_map = empty_map();
+        grid_map.grid_mut().set(5, 5, GridNodeValue::Obstacle);
+        let path = [Vec2::new(5.0, 5.0), Vec2::new(95.0, 95.0)];
+        assert_eq!(
+            violations(grid_map, &path),
+            vec![PathViolation::Collision {
+                segment: 0,
+                from: path[0],
+                to: path[1],
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_segments_into_no_go_zones() {
+        let mut grid_map = empty_map();
+        grid_map.zones_mut().add(Zone::rect("keep out", quad(40.0, 40.0, 20.0, 20.0), ZoneKind::NoGo));
+        let path = [Vec2::new(5.0, 5.0), Vec2::new(95.0, 95.0)];
+        assert_eq!(
+            violations(grid_map, &path),
+            vec![PathViolation::NoGoZone {
+                segment: 0,
+                from: path[0],
+                to: path[1],
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_over_the_hazard_budget() {
+        let mut grid_map = empty_map();
+        let occupancy = grid_map.enable_occupancy(OccupancyConfig {
+            prior: 0.0,
+            ..Default::default()
+        });
+        for y in 0..10 {
+            occupancy.set_probability(5, y, 0.5);
+        }
+        let problem = Problem::new(Arc::new(grid_map), Vec2::new(5.0, 55.0), Vec2::new(95.0, 55.0)).with_risk(RiskConfig {
+            weight: 0.0,
+            bound: 0.1,
+        });
+        let path = [problem.start(), problem.goal()];
+        let found = PathValidator::default().violations(&problem, &path);
+        assert!(matches!(found[..], [PathViolation::HazardBudget { hazard, budget }] if hazard > budget));
+    }
+
+    #[test]
+    fn rejects_squeezing_between_diagonally_touching_obstacles() {
+        // Cells (4, 4) and (5, 5) share only the corner (50, 50)
+        let mut grid_map = empty_map();
+        grid_map.grid_mut().set(4, 4, GridNodeValue::Obstacle);
+        grid_map.grid_mut().set(5, 5, GridNodeValue::Obstacle);
+        let world: &dyn Geometry = &grid_map;
+
+        assert!(!world.is_segment_clear(Vec2::new(5.0, 95.0), Vec2::new(95.0, 5.0), 0.0));
+        // Grazing a single corner or running along an edge stays clear
+        assert!(world.is_segment_clear(Vec2::new(25.0, 95.0), Vec2::new(95.0, 25.0), 0.0));
+        assert!(world.is_segment_clear(Vec2::new(5.0, 40.0), Vec2::new(95.0, 40.0), 0.0));
+    }
+
+    #[test]
+    fn strategies_return_valid_paths_on_the_presets() {
+        for name in gridmaker::preset_names() {
+            let preset = gridmaker::preset(name, 36).unwrap();
+            let navmesh = NavMeshStrategy::new(NavMesh::new(&preset.grid_map, 0.0));
+            let visibility = VisibilityGraphStrategy::new(VisibilityGraph::new(&preset.grid_map));
+            let medial_axis = MedialAxisStrategy::new(MedialAxisRoadmap::new(&preset.grid_map, MedialAxisConfig::default()));
+            let aco = AcoStrategy {
+                min_ant_count: 100,
+                ..Default::default()
+            };
+            let strategies: [(&str, &dyn Strategy); 5] = [
+                ("a_star", &AStarStrategy::default()),
+                ("aco", &aco),
+                ("navmesh", &navmesh),
+                ("visibility", &visibility),
+                ("medial_axis", &medial_axis),
+            ];
+            let pso = PsoStrategy {
+                swarms_count: 20,
+                iterate_count: 20,
+                ..Default::default()
+            };
+            let optimizers: [(&str, &dyn PathOptimizer); 3] = [
+                ("pso", &pso),
+                ("shortcut", &ShortcutOptimizer::default()),
+                ("smoothing", &SmoothingOptimizer::default()),
+            ];
+
+            let queries = preset.suggested_queries.clone();
+            let world: Arc<dyn Geometry> = Arc::new(preset.grid_map);
+            for (start, goal) in queries {
+                let problem = Problem::new(Arc::clone(&world), start, goal);
+                let context = PlanContext::new();
+                for (strategy_name, strategy) in strategies {
+                    let planned = match strategy.path_finding(&problem, &context) {
+                        Ok(planned) => planned,
+                        // Ants may give up, only the paths they do return have to be valid
+                        Err(PlanError::IterationBudgetExhausted) => continue,
+                        Err(err) => panic!("{} on {}: {}", strategy_name, name, err),
+                    };
+                    let found = PathValidator::default().violations(&problem, &planned.path);
+                    assert!(found.is_empty(), "{} on {}: {:?}", strategy_name, name, found);
+
+                    for (optimizer_name, optimizer) in optimizers {
+                        let optimized = optimizer
+                            .optimize(&problem, &planned.path, &context)
+                            .unwrap_or_else(|err| panic!("{} after {} on {}: {}", optimizer_name, strategy_name, name, err));
+                        let found = PathValidator::default().violations(&problem, &optimized.path);
+                        assert!(found.is_empty(), "{} after {} on {}: {:?}", optimizer_name, strategy_name, name, found);
+                    }
+                }
+            }
+        }
+    }
+}
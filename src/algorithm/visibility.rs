@@ -0,0 +1,45 @@
+use crate::algorithm::context::*;
+use crate::algorithm::error::*;
+use crate::algorithm::problem::*;
+use crate::algorithm::result::*;
+use crate::algorithm::strategy::*;
+use crate::algorithm::validator::*;
+use crate::world::geometry::CountingGeometry;
+use crate::world::visibility::VisibilityGraph;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Exact any-angle shortest paths on a prebuilt visibility graph of the problem's world.
+pub struct VisibilityGraphStrategy {
+    graph: Arc<VisibilityGraph>,
+}
+
+impl VisibilityGraphStrategy {
+    pub fn new(graph: VisibilityGraph) -> Self { Self { graph: Arc::new(graph) } }
+
+    pub fn with_shared(graph: Arc<VisibilityGraph>) -> Self { Self { graph } }
+
+    pub fn graph(&self) -> &VisibilityGraph { &self.graph }
+}
+
+impl Strategy for VisibilityGraphStrategy {
+    /// Shortest by length only, a path crossing a no-go zone is rejected rather than rerouted.
+    fn path_finding(&self, problem: &Problem, context: &PlanContext) -> Result<PlanResult, PlanError> {
+        let started = Instant::now();
+        problem.validate()?;
+        context.check(0)?;
+
+        let world = CountingGeometry::new(problem.world());
+        let (path, expanded) = self.graph.find_path(&world, problem.start(), problem.goal()).ok_or(PlanError::Unreachable)?;
+        if !path.windows(2).all(|segment| problem.is_segment_allowed(segment[0], segment[1])) {
+            return Err(PlanError::Unreachable);
+        }
+
+        let stats = SearchStats {
+            nodes_expanded: expanded,
+            raycasts: world.raycasts(),
+            ..Default::default()
+        };
+        certify(problem, PlanResult::new(problem, path, started.elapsed(), stats))
+    }
+}
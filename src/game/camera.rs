@@ -4,6 +4,12 @@ pub struct CameraManager {
     camera: Camera2D,
 }
 
+impl Default for CameraManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CameraManager {
     pub fn new() -> Self {
         Self {
@@ -1,16 +1,18 @@
-// use crate::algorithm::a_star::AStarStrategy;
-use crate::algorithm::aco::AcoStrategy;
+use crate::algorithm::anytime::Improvement;
+use crate::algorithm::context::PlanContext;
+use crate::algorithm::error::PlanError;
 use crate::algorithm::problem::Problem;
-use crate::algorithm::pso::PsoStrategy;
-use crate::algorithm::strategy::Strategy;
+use crate::algorithm::registry::{PlannerConfig, StrategyRegistry};
+use crate::algorithm::service::{Delivery, JobId, JobResult, PlanJob, PlannerService};
 use crate::game::camera::CameraManager;
 use crate::game::map_renderer::MapRenderer;
 use crate::game::path_renderer::PathRenderer;
-use crate::game::temporary_dot_renderer::{draw_all_temporary_dots, update_temporary_dots};
+use crate::game::temporary_dot_renderer::{TemporaryDotObserver, draw_all_temporary_dots, update_temporary_dots};
 use crate::game::ui::UIManager;
+use crate::world::clearance::ClearanceField;
 use macroquad::prelude::*;
 use std::sync::{Arc, mpsc};
-use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub struct RenderConfig {
@@ -38,8 +40,13 @@ pub struct GameManager {
     start_pos: Option<Vec2>,
     end_pos: Option<Vec2>,
     render_config: RenderConfig,
-    pathfinding_receiver: Option<mpsc::Receiver<(Option<Vec<Vec2>>, Option<Vec<Vec2>>)>>,
+    planner: PlannerService,
+    pathfinding_job: Option<JobId>,
+    pathfinding_receiver: Option<mpsc::Receiver<JobResult>>,
+    progress_receiver: Option<mpsc::Receiver<Improvement>>,
     grid_map: Arc<crate::world::grid::GridMap>,
+    clearance: ClearanceField,
+    planner_config: PlannerConfig,
 
     map_renderer: Box<MapRenderer>,
     path_renderer: Box<PathRenderer>,
@@ -61,8 +68,14 @@ impl GameManager {
             start_pos: None,
             end_pos: None,
             render_config,
+            // One worker, a new search replaces the running one rather than queueing behind it
+            planner: PlannerService::new(1, StrategyRegistry::default()),
+            pathfinding_job: None,
             pathfinding_receiver: None,
+            progress_receiver: None,
+            clearance: ClearanceField::new(&grid_map),
             grid_map,
+            planner_config: PlannerConfig::default(),
             map_renderer,
             path_renderer,
             camera_manager,
@@ -105,6 +118,14 @@ impl GameManager {
         self.path_renderer.as_mut()
     }
 
+    pub fn planner_config(&self) -> &PlannerConfig {
+        &self.planner_config
+    }
+
+    pub fn set_planner_config(&mut self, planner_config: PlannerConfig) {
+        self.planner_config = planner_config;
+    }
+
     pub fn start_pos(&self) -> Option<Vec2> {
         self.start_pos
     }
@@ -113,6 +134,14 @@ impl GameManager {
         self.end_pos
     }
 
+    pub fn set_start_pos(&mut self, pos: Vec2) {
+        self.start_pos = Some(pos);
+    }
+
+    pub fn set_end_pos(&mut self, pos: Vec2) {
+        self.end_pos = Some(pos);
+    }
+
     fn start_pathfinding(&mut self) {
         if let (Some(start), Some(end)) = (self.start_pos, self.end_pos) {
             self.set_state(GameState::Loading);
@@ -120,64 +149,120 @@ impl GameManager {
 
             let (sender, receiver) = mpsc::channel();
             self.pathfinding_receiver = Some(receiver);
+            // The stages stream their improving paths so the current best is drawn while the search runs
+            let (progress_sender, progress_receiver) = mpsc::channel();
+            self.progress_receiver = Some(progress_receiver);
 
+            // Fresh seed per run unless configured, it is shown with the results so a run can be replayed
+            let seed = self.planner_config.seed.unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos() as u64)
+            });
+            let context = PlanContext::new()
+                .with_seed(seed)
+                .with_improvements(progress_sender)
+                .with_observer(Arc::new(TemporaryDotObserver {
+                    color: WHITE,
+                    radius: 10.0,
+                    time_seconds: 0.1,
+                }));
+            let spec = self.planner_config.pipeline_spec();
             let grid_map = Arc::clone(&self.grid_map);
+            let problem = Arc::new(Problem::new(grid_map, start, end));
+            let job = PlanJob::new(problem, spec).with_context(context);
 
-            thread::spawn(move || {
-                let problem = Problem::new(grid_map, start, end);
-                // let path = AStarStrategy {}.path_finding(&problem);
-                let aco_path = AcoStrategy {
-                    node_dist: 100.0 / 2.0,
-                    alpha: 1.0,
-                    beta: 5.0,
-                    deposit_constant: 2000.0,
-                    evaporation: 0.2,
-                    init_pheromone: 0.05,
-                    min_ant_count: 1000,
-                    max_ant_try: 500,
+            let delivery = Delivery::Callback(Box::new(move |outcome: JobResult| {
+                // A cancelled job was replaced by a newer one, whose result is the one to show
+                if !matches!(outcome.result, Err(PlanError::Cancelled)) {
+                    let _ = sender.send(outcome);
                 }
-                .path_finding(&problem)
-                .or_else(|| {
-                    std::println!("================================== \n== NOT FOUND PATH");
-                    None
-                });
-
-                let pso_path = if let Some(ref aco_solution) = aco_path {
-                    Some(
-                        PsoStrategy {
-                            init_random_offset: 20.0,
-                            swarms_count: 100,
-                            inertia_weight: 0.9,
-                            local_factor: 1.0,
-                            global_factor: 1.0,
-                            iterate_count: 100,
-                            max_velocity: 1000.0,
-                        }
-                        .upgrade_path(&problem, aco_solution),
-                    )
-                } else {
-                    None
-                };
-
-                let _ = sender.send((aco_path, pso_path));
-            });
+            }));
+
+            let submitted = match self.pathfinding_job.take() {
+                Some(running) => self.planner.replace(running, job, delivery),
+                None => self.planner.submit(job, delivery),
+            };
+            match submitted {
+                Ok(id) => self.pathfinding_job = Some(id),
+                Err(err) => {
+                    self.ui_manager_mut().stop_timer();
+                    self.ui_manager_mut().set_plan_summaries(vec![format!("Invalid planner config: {}", err)]);
+                    self.set_state(GameState::Idle);
+                }
+            }
+        }
+    }
+
+    /// Stops the running search, its worker moves on at the strategy's next check and its result is dropped.
+    fn cancel_pathfinding(&mut self) {
+        if let Some(job) = self.pathfinding_job.take() {
+            self.planner.cancel(job);
+        }
+        self.pathfinding_receiver = None;
+        self.progress_receiver = None;
+    }
+
+    /// Stops the running search but keeps the best paths it streamed so far on screen.
+    fn finish_pathfinding(&mut self) {
+        if self.pathfinding_receiver.is_none() {
+            return;
+        }
+        self.receive_progress();
+        self.cancel_pathfinding();
+        self.ui_manager_mut().stop_timer();
+        self.ui_manager_mut()
+            .set_plan_summaries(vec!["Stopped early, showing the best paths so far".to_string()]);
+        self.set_state(GameState::Idle);
+    }
+
+    /// Draws the improvements queued since the last frame, the ACO stage as the ACO path and PSO as the PSO path.
+    fn receive_progress(&mut self) {
+        let Some(receiver) = &self.progress_receiver else {
+            return;
+        };
+        for improvement in receiver.try_iter() {
+            match improvement.stage {
+                0 => self.path_renderer.set_aco_path(improvement.result.path),
+                _ => self.path_renderer.set_pso_path(improvement.result.path),
+            }
         }
     }
 
     pub fn update(&mut self) {
+        self.receive_progress();
+
         // Check if we have a pending pathfinding result
-        if let Some(receiver) = &mut self.pathfinding_receiver {
-            if let Ok((aco_path, pso_path)) = receiver.try_recv() {
-                if let Some(aco) = aco_path {
-                    self.path_renderer_mut().set_aco_path(aco);
+        if let Some(receiver) = &mut self.pathfinding_receiver
+            && let Ok(outcome) = receiver.try_recv()
+        {
+            let summaries = match outcome.result {
+                Ok(plan) => {
+                    let mut summaries: Vec<String> = plan
+                        .stages
+                        .iter()
+                        .map(|stage| format!("{} {}", stage.name.to_uppercase(), stage.result.summary()))
+                        .collect();
+                    // Tight means closer than one cell to an obstacle
+                    let metrics = plan.metrics(&self.clearance, self.grid_map.cell_size());
+                    summaries.push(format!("PATH {}", metrics.summary()));
+                    let mut stages = plan.stages.into_iter();
+                    if let Some(aco) = stages.next() {
+                        self.path_renderer_mut().set_aco_path(aco.result.path);
+                    }
+                    if let Some(pso) = stages.next() {
+                        self.path_renderer_mut().set_pso_path(pso.result.path);
+                    }
+                    summaries
                 }
-                if let Some(pso) = pso_path {
-                    self.path_renderer_mut().set_pso_path(pso);
-                }
-                self.ui_manager_mut().stop_timer();
-                self.pathfinding_receiver = None;
-                self.set_state(GameState::Idle);
-            }
+                Err(err) => vec![format!("No path: {}", err)],
+            };
+            self.ui_manager_mut().set_plan_summaries(summaries);
+            self.ui_manager_mut().stop_timer();
+            self.pathfinding_receiver = None;
+            self.pathfinding_job = None;
+            self.progress_receiver = None;
+            self.set_state(GameState::Idle);
         }
 
         // Update temporary dots
@@ -189,10 +274,8 @@ impl GameManager {
     }
 
     fn handle_input(&mut self) {
-        if is_key_pressed(KeyCode::G) {
-            if self.start_pos.is_some() && self.end_pos.is_some() {
-                self.start_pathfinding();
-            }
+        if is_key_pressed(KeyCode::G) && self.start_pos.is_some() && self.end_pos.is_some() {
+            self.start_pathfinding();
         }
         if is_key_pressed(KeyCode::S) {
             self.set_state(GameState::SetStart);
@@ -200,10 +283,15 @@ impl GameManager {
         if is_key_pressed(KeyCode::E) {
             self.set_state(GameState::SetEnd);
         }
+        if is_key_pressed(KeyCode::F) {
+            self.finish_pathfinding();
+        }
         if is_key_pressed(KeyCode::C) {
+            self.cancel_pathfinding();
             self.set_state(GameState::Idle);
             self.path_renderer_mut().unset_paths();
             self.ui_manager_mut().reset_timer();
+            self.ui_manager_mut().set_plan_summaries(Vec::new());
         }
 
         // Handle mouse clicks for setting start and end positions
@@ -230,7 +318,7 @@ impl GameManager {
     fn render(&self) {
         self.map_renderer.draw();
         self.ui_manager
-            .draw(&self.camera_manager.camera(), self.get_state_description());
+            .draw(self.camera_manager.camera(), self.get_state_description());
         self.path_renderer.draw();
 
         // Draw all temporary dots
@@ -1,11 +1,13 @@
 use crate::game::game::RenderConfig;
 use crate::world::grid::GridMap;
 use crate::world::grid::GridNodeValue;
+use crate::world::zone::ZoneKind;
 use macroquad::prelude::*;
 
 pub struct MapRenderer {
     config: RenderConfig,
     mesh: Option<Mesh>,
+    zones: Vec<(Vec<Vec2>, Color)>,
 
     max_bound: Vec2,
 }
@@ -15,6 +17,7 @@ impl MapRenderer {
         Self {
             config,
             mesh: None,
+            zones: Vec::new(),
             max_bound: Vec2::ZERO,
         }
     }
@@ -70,6 +73,20 @@ impl MapRenderer {
             }
         }
 
+        self.zones = map
+            .zones()
+            .zones()
+            .iter()
+            .map(|zone| {
+                let color = match zone.kind {
+                    ZoneKind::NoGo => MAGENTA,
+                    ZoneKind::Preferred { .. } => LIME,
+                    ZoneKind::SpeedLimited { .. } => ORANGE,
+                };
+                (zone.area.vertices().to_vec(), color)
+            })
+            .collect();
+
         if !vertices.is_empty() {
             let mesh = Mesh {
                 vertices,
@@ -86,6 +103,14 @@ impl MapRenderer {
         if let Some(mesh) = self.mesh.as_ref() {
             draw_mesh(mesh);
         }
+
+        for (outline, color) in self.zones.iter() {
+            for i in 0..outline.len() {
+                let from = outline[i];
+                let to = outline[(i + 1) % outline.len()];
+                draw_line(from.x, from.y, to.x, to.y, 6.0, *color);
+            }
+        }
     }
 
     pub fn mesh(&self) -> Option<&Mesh> { self.mesh.as_ref() }
@@ -1,4 +1,5 @@
 pub mod camera;
+#[allow(clippy::module_inception)]
 pub mod game;
 pub mod map_renderer;
 pub mod path_renderer;
@@ -39,34 +39,34 @@ impl PathRenderer {
 
     pub fn draw(&self) {
         // Draw ACO path in the original color
-        if let Some(path) = &self.aco_path {
-            if path.len() >= 2 {
-                for i in 0..path.len() - 1 {
-                    draw_line(
-                        path[i].x,
-                        path[i].y,
-                        path[i + 1].x,
-                        path[i + 1].y,
-                        self.config.path_thickness,
-                        self.config.path_color,
-                    );
-                }
+        if let Some(path) = &self.aco_path
+            && path.len() >= 2
+        {
+            for i in 0..path.len() - 1 {
+                draw_line(
+                    path[i].x,
+                    path[i].y,
+                    path[i + 1].x,
+                    path[i + 1].y,
+                    self.config.path_thickness,
+                    self.config.path_color,
+                );
             }
         }
 
         // Draw PSO path in a different color (blue)
-        if let Some(path) = &self.pso_path {
-            if path.len() >= 2 {
-                for i in 0..path.len() - 1 {
-                    draw_line(
-                        path[i].x,
-                        path[i].y,
-                        path[i + 1].x,
-                        path[i + 1].y,
-                        self.config.path_thickness,
-                        GREEN, // PSO path in blue
-                    );
-                }
+        if let Some(path) = &self.pso_path
+            && path.len() >= 2
+        {
+            for i in 0..path.len() - 1 {
+                draw_line(
+                    path[i].x,
+                    path[i].y,
+                    path[i + 1].x,
+                    path[i + 1].y,
+                    self.config.path_thickness,
+                    GREEN, // PSO path in blue
+                );
             }
         }
     }
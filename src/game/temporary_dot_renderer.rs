@@ -1,3 +1,4 @@
+use crate::algorithm::observer::PlannerObserver;
 use macroquad::prelude::*;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -16,6 +17,12 @@ pub struct TemporaryDotRenderer {
     next_id: u64,
 }
 
+impl Default for TemporaryDotRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TemporaryDotRenderer {
     pub fn new() -> Self {
         Self {
@@ -107,4 +114,17 @@ pub fn draw_all_temporary_dots() {
     let renderer = get_temporary_dot_renderer();
     let renderer = renderer.lock().unwrap();
     renderer.draw();
-}
\ No newline at end of file
+}
+
+/// Observer that marks every ant step with a short-lived dot.
+pub struct TemporaryDotObserver {
+    pub color: Color,
+    pub radius: f32,
+    pub time_seconds: f64,
+}
+
+impl PlannerObserver for TemporaryDotObserver {
+    fn ant_moved(&self, _ant: usize, _from: Vec2, to: Vec2) {
+        draw_temporary_dot(to, self.color, self.radius, self.time_seconds);
+    }
+}
@@ -8,6 +8,7 @@ pub struct UIManager {
     timer_start: Option<Instant>,
     timer_elapsed: Option<Duration>,
     show_timer: bool,
+    plan_summaries: Vec<String>,
 }
 
 impl UIManager {
@@ -19,6 +20,7 @@ impl UIManager {
             timer_start: None,
             timer_elapsed: None,
             show_timer: false,
+            plan_summaries: Vec::new(),
         }
     }
 
@@ -38,10 +40,8 @@ impl UIManager {
     pub fn get_elapsed_ms(&self) -> Option<f64> {
         if let Some(elapsed) = self.timer_elapsed {
             Some(elapsed.as_secs_f64() * 1000.0)
-        } else if let Some(start_time) = self.timer_start {
-            Some(start_time.elapsed().as_secs_f64() * 1000.0)
         } else {
-            None
+            self.timer_start.map(|start_time| start_time.elapsed().as_secs_f64() * 1000.0)
         }
     }
 
@@ -51,6 +51,10 @@ impl UIManager {
         self.show_timer = false;
     }
 
+    pub fn set_plan_summaries(&mut self, summaries: Vec<String>) {
+        self.plan_summaries = summaries;
+    }
+
     pub fn draw(&self, camera: &Camera2D, state_description: &str) {
         let screen_top_left = camera.screen_to_world(vec2(0.0, 0.0));
         let screen_bottom_left = camera.screen_to_world(vec2(0.0, screen_height()));
@@ -58,7 +62,7 @@ impl UIManager {
         if self.show_state {
             let offset = self.font_size * 1.0;
             draw_text(
-                &format!("> {}", state_description),
+                format!("> {}", state_description).as_str(),
                 screen_top_left.x + offset,
                 screen_top_left.y + offset,
                 self.font_size,
@@ -66,23 +70,38 @@ impl UIManager {
             );
         }
 
-        // Timer display in top-right corner
-        if self.show_timer {
-            if let Some(elapsed_ms) = self.get_elapsed_ms() {
-                let screen_top_right = camera.screen_to_world(vec2(screen_width(), 0.0));
-                let offset = self.font_size * 1.0;
-                let timer_text = format!("{:.2}ms", elapsed_ms);
-
+        // Plan statistics below the state line
+        {
+            let offset = self.font_size * 1.0;
+            let line_height = self.font_size * 0.6;
+            for (i, summary) in self.plan_summaries.iter().enumerate() {
                 draw_text(
-                    &timer_text,
-                    screen_top_right.x - offset - timer_text.len() as f32 * self.font_size * 0.6,
-                    screen_top_right.y + offset,
-                    self.font_size,
-                    YELLOW,
+                    summary,
+                    screen_top_left.x + offset,
+                    screen_top_left.y + offset + line_height * (i + 1) as f32,
+                    self.font_size * 0.5,
+                    LIGHTGRAY,
                 );
             }
         }
 
+        // Timer display in top-right corner
+        if self.show_timer
+            && let Some(elapsed_ms) = self.get_elapsed_ms()
+        {
+            let screen_top_right = camera.screen_to_world(vec2(screen_width(), 0.0));
+            let offset = self.font_size * 1.0;
+            let timer_text = format!("{:.2}ms", elapsed_ms);
+
+            draw_text(
+                &timer_text,
+                screen_top_right.x - offset - timer_text.len() as f32 * self.font_size * 0.6,
+                screen_top_right.y + offset,
+                self.font_size,
+                YELLOW,
+            );
+        }
+
         if self.show_controls {
             let offset = self.font_size * 1.0;
             let line_height = self.font_size * 1.2;
@@ -118,6 +137,13 @@ impl UIManager {
                 self.font_size,
                 WHITE,
             );
+            draw_text(
+                "[F] Finish",
+                x + line_width * 2.0,
+                y + line_height * 0.0,
+                self.font_size,
+                WHITE,
+            );
         }
     }
 
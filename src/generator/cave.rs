@@ -0,0 +1,132 @@
+use crate::generator::{seeded_rng, set_border, MapGenerator};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+use std::collections::VecDeque;
+
+/// Cellular automata caves, random noise smoothed with the 4-5 rule.
+#[derive(Debug, Clone)]
+pub struct CaveGenerator {
+    /// Initial chance of a cell being rock.
+    pub fill_ratio: f32,
+    pub iterations: usize,
+    /// Fill every cave except the largest so the free space is connected.
+    pub keep_largest_region: bool,
+}
+
+impl Default for CaveGenerator {
+    fn default() -> Self {
+        Self {
+            fill_ratio: 0.45,
+            iterations: 5,
+            keep_largest_region: true,
+        }
+    }
+}
+
+impl MapGenerator for CaveGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap {
+        let rng = seeded_rng(seed);
+        let mut grid_map = GridMap::new(config.clone());
+        let width = grid_map.width();
+        let height = grid_map.height();
+
+        let mut rock: Vec<bool> = (0..width * height)
+            .map(|_| rng.gen_range(0.0, 1.0) < self.fill_ratio)
+            .collect();
+
+        for _ in 0..self.iterations {
+            let mut next = rock.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    let walls = rock_neighbours(&rock, width, height, x, y);
+                    if walls > 4 {
+                        next[y * width + x] = true;
+                    } else if walls < 4 {
+                        next[y * width + x] = false;
+                    }
+                }
+            }
+            rock = next;
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                if rock[y * width + x] {
+                    grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+                }
+            }
+        }
+        set_border(&mut grid_map);
+
+        if self.keep_largest_region {
+            fill_small_regions(&mut grid_map);
+        }
+
+        grid_map
+    }
+}
+
+/// Rock cells among the 8 neighbours, out of bounds counts as rock.
+fn rock_neighbours(rock: &[bool], width: usize, height: usize, x: usize, y: usize) -> usize {
+    let mut count = 0;
+    for dy in -1..=1_isize {
+        for dx in -1..=1_isize {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let nx = x as isize + dx;
+            let ny = y as isize + dy;
+            if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize || rock[ny as usize * width + nx as usize] {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn fill_small_regions(grid_map: &mut GridMap) {
+    let width = grid_map.width();
+    let height = grid_map.height();
+    let mut region = vec![usize::MAX; width * height];
+    let mut region_sizes: Vec<usize> = Vec::new();
+
+    for start_y in 0..height {
+        for start_x in 0..width {
+            if region[start_y * width + start_x] != usize::MAX || grid_map.grid().is_air(start_x, start_y) != Some(true) {
+                continue;
+            }
+
+            let id = region_sizes.len();
+            let mut size = 0;
+            let mut queue = VecDeque::from([(start_x, start_y)]);
+            region[start_y * width + start_x] = id;
+
+            while let Some((x, y)) = queue.pop_front() {
+                size += 1;
+                for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                        continue;
+                    };
+                    if nx < width && ny < height && region[ny * width + nx] == usize::MAX && grid_map.grid().is_air(nx, ny) == Some(true) {
+                        region[ny * width + nx] = id;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+            region_sizes.push(size);
+        }
+    }
+
+    let Some(largest) = (0..region_sizes.len()).max_by_key(|&id| region_sizes[id]) else {
+        return;
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let id = region[y * width + x];
+            if id != usize::MAX && id != largest {
+                grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::generator::{fill, seeded_rng, MapGenerator};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+use quad_rand::ChooseRandom;
+
+/// Recursive backtracker maze carved on odd cells, one cell wide corridors.
+#[derive(Debug, Clone)]
+pub struct MazeGenerator {
+    /// Fraction of dead ends opened into a neighbouring corridor, 0 gives a perfect maze.
+    pub braid: f32,
+}
+
+impl MazeGenerator {
+    pub fn perfect() -> Self { Self { braid: 0.0 } }
+    pub fn braided(braid: f32) -> Self { Self { braid } }
+}
+
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+impl MapGenerator for MazeGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap {
+        let rng = seeded_rng(seed);
+        let mut grid_map = GridMap::new(config.clone());
+        fill(&mut grid_map, GridNodeValue::Obstacle);
+
+        // Rooms sit on odd coordinates, the cells between two rooms are the walls to knock down
+        let rooms_x = grid_map.width().saturating_sub(1) / 2;
+        let rooms_y = grid_map.height().saturating_sub(1) / 2;
+        if rooms_x == 0 || rooms_y == 0 {
+            return grid_map;
+        }
+
+        let room_cell = |rx: usize, ry: usize| (rx * 2 + 1, ry * 2 + 1);
+        let mut visited = vec![false; rooms_x * rooms_y];
+        let mut stack = vec![(rng.gen_range(0, rooms_x), rng.gen_range(0, rooms_y))];
+        visited[stack[0].1 * rooms_x + stack[0].0] = true;
+        let (x, y) = room_cell(stack[0].0, stack[0].1);
+        grid_map.grid_mut().set(x, y, GridNodeValue::Air);
+
+        while let Some(&(rx, ry)) = stack.last() {
+            let mut directions = DIRECTIONS;
+            directions.shuffle_with_state(&rng);
+
+            let next = directions.iter().find_map(|(dx, dy)| {
+                let nx = rx.checked_add_signed(*dx)?;
+                let ny = ry.checked_add_signed(*dy)?;
+                (nx < rooms_x && ny < rooms_y && !visited[ny * rooms_x + nx]).then_some((nx, ny))
+            });
+
+            match next {
+                Some((nx, ny)) => {
+                    visited[ny * rooms_x + nx] = true;
+                    let (x0, y0) = room_cell(rx, ry);
+                    let (x1, y1) = room_cell(nx, ny);
+                    grid_map.grid_mut().set((x0 + x1) / 2, (y0 + y1) / 2, GridNodeValue::Air);
+                    grid_map.grid_mut().set(x1, y1, GridNodeValue::Air);
+                    stack.push((nx, ny));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        if self.braid > 0.0 {
+            for ry in 0..rooms_y {
+                for rx in 0..rooms_x {
+                    let (x, y) = room_cell(rx, ry);
+                    let mut walls: Vec<(usize, usize)> = DIRECTIONS
+                        .iter()
+                        .filter_map(|(dx, dy)| {
+                            let nx = rx.checked_add_signed(*dx)?;
+                            let ny = ry.checked_add_signed(*dy)?;
+                            if nx >= rooms_x || ny >= rooms_y {
+                                return None;
+                            }
+                            let wall = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+                            (grid_map.grid().get(wall.0, wall.1) == Some(GridNodeValue::Obstacle)).then_some(wall)
+                        })
+                        .collect();
+
+                    let open_sides = DIRECTIONS
+                        .iter()
+                        .filter(|(dx, dy)| {
+                            let wx = x.checked_add_signed(*dx);
+                            let wy = y.checked_add_signed(*dy);
+                            matches!((wx, wy), (Some(wx), Some(wy)) if grid_map.grid().is_air(wx, wy) == Some(true))
+                        })
+                        .count();
+
+                    if open_sides == 1 && !walls.is_empty() && rng.gen_range(0.0, 1.0) < self.braid {
+                        walls.shuffle_with_state(&rng);
+                        grid_map.grid_mut().set(walls[0].0, walls[0].1, GridNodeValue::Air);
+                    }
+                }
+            }
+        }
+
+        grid_map
+    }
+}
@@ -0,0 +1,104 @@
+pub mod cave;
+pub mod maze;
+pub mod noise;
+pub mod rooms;
+pub mod scatter;
+
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+use quad_rand::RandGenerator;
+
+/// Procedural map source, the same seed always produces the same map.
+pub trait MapGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap;
+}
+
+pub(crate) fn seeded_rng(seed: u64) -> RandGenerator {
+    let rng = RandGenerator::new();
+    rng.srand(seed);
+    rng
+}
+
+pub(crate) fn fill(grid_map: &mut GridMap, val: GridNodeValue) {
+    for y in 0..grid_map.height() {
+        for x in 0..grid_map.width() {
+            grid_map.grid_mut().set(x, y, val);
+        }
+    }
+}
+
+pub(crate) fn fill_rect(grid_map: &mut GridMap, x: usize, y: usize, w: usize, h: usize, val: GridNodeValue) {
+    for dy in 0..h {
+        for dx in 0..w {
+            grid_map.grid_mut().set(x + dx, y + dy, val);
+        }
+    }
+}
+
+pub(crate) fn set_border(grid_map: &mut GridMap) {
+    let width = grid_map.width();
+    let height = grid_map.height();
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    for x in 0..width {
+        grid_map.grid_mut().set(x, 0, GridNodeValue::Obstacle);
+        grid_map.grid_mut().set(x, height - 1, GridNodeValue::Obstacle);
+    }
+    for y in 0..height {
+        grid_map.grid_mut().set(0, y, GridNodeValue::Obstacle);
+        grid_map.grid_mut().set(width - 1, y, GridNodeValue::Obstacle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cave::CaveGenerator;
+    use super::maze::MazeGenerator;
+    use super::noise::NoiseGenerator;
+    use super::rooms::RoomsGenerator;
+    use super::scatter::ScatterGenerator;
+    use super::*;
+
+    fn generators() -> Vec<(&'static str, Box<dyn MapGenerator>)> {
+        vec![
+            ("cave", Box::new(CaveGenerator::default())),
+            ("perfect maze", Box::new(MazeGenerator::perfect())),
+            ("braided maze", Box::new(MazeGenerator::braided(0.5))),
+            ("noise", Box::new(NoiseGenerator::default())),
+            ("rooms", Box::new(RoomsGenerator::default())),
+            ("scatter", Box::new(ScatterGenerator::default())),
+        ]
+    }
+
+    fn cells(grid_map: &GridMap) -> Vec<Option<GridNodeValue>> {
+        (0..grid_map.height())
+            .flat_map(|y| (0..grid_map.width()).map(move |x| (x, y)))
+            .map(|(x, y)| grid_map.grid().get(x, y))
+            .collect()
+    }
+
+    const CONFIG: WorldConfig = WorldConfig {
+        grid_size: (41, 31),
+        cell_size: 10.0,
+    };
+
+    #[test]
+    fn same_seed_gives_the_same_map() {
+        for (name, generator) in generators() {
+            let first = generator.generate(&CONFIG, 42);
+            let second = generator.generate(&CONFIG, 42);
+            assert_eq!(cells(&first), cells(&second), "{} differs between runs", name);
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_maps() {
+        for (name, generator) in generators() {
+            let first = generator.generate(&CONFIG, 1);
+            let second = generator.generate(&CONFIG, 2);
+            assert_ne!(cells(&first), cells(&second), "{} ignores its seed", name);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::generator::{seeded_rng, set_border, MapGenerator};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+use quad_rand::ChooseRandom;
+
+/// Fractal Perlin noise thresholded into obstacles, gives organic blob terrain.
+#[derive(Debug, Clone)]
+pub struct NoiseGenerator {
+    /// Size in cells of one noise period of the first octave.
+    pub scale: f32,
+    pub octaves: u32,
+    /// Amplitude multiplier between consecutive octaves.
+    pub persistence: f32,
+    /// Noise values above this become obstacles, noise lies roughly in `[-1, 1]`.
+    pub threshold: f32,
+    pub border: bool,
+}
+
+impl Default for NoiseGenerator {
+    fn default() -> Self {
+        Self {
+            scale: 8.0,
+            octaves: 3,
+            persistence: 0.5,
+            threshold: 0.15,
+            border: true,
+        }
+    }
+}
+
+impl MapGenerator for NoiseGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap {
+        let perlin = Perlin::new(seed);
+        let mut grid_map = GridMap::new(config.clone());
+        let scale = self.scale.max(f32::EPSILON);
+
+        for y in 0..grid_map.height() {
+            for x in 0..grid_map.width() {
+                let mut value = 0.0;
+                let mut amplitude = 1.0;
+                let mut frequency = 1.0 / scale;
+                let mut total_amplitude = 0.0;
+                for _ in 0..self.octaves.max(1) {
+                    value += perlin.noise(x as f32 * frequency, y as f32 * frequency) * amplitude;
+                    total_amplitude += amplitude;
+                    amplitude *= self.persistence;
+                    frequency *= 2.0;
+                }
+
+                if value / total_amplitude > self.threshold {
+                    grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+                }
+            }
+        }
+
+        if self.border {
+            set_border(&mut grid_map);
+        }
+
+        grid_map
+    }
+}
+
+/// Classic 2D gradient noise with a seeded permutation table.
+struct Perlin {
+    perm: [u8; 512],
+}
+
+impl Perlin {
+    fn new(seed: u64) -> Self {
+        let rng = seeded_rng(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle_with_state(&rng);
+
+        let mut perm = [0; 512];
+        for i in 0..512 {
+            perm[i] = table[i & 255];
+        }
+        Self { perm }
+    }
+
+    fn noise(&self, x: f32, y: f32) -> f32 {
+        let xi = (x.floor() as i64 & 255) as usize;
+        let yi = (y.floor() as i64 & 255) as usize;
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+
+        let u = fade(xf);
+        let v = fade(yf);
+
+        let aa = self.perm[self.perm[xi] as usize + yi];
+        let ab = self.perm[self.perm[xi] as usize + yi + 1];
+        let ba = self.perm[self.perm[xi + 1] as usize + yi];
+        let bb = self.perm[self.perm[xi + 1] as usize + yi + 1];
+
+        let x1 = lerp(grad(aa, xf, yf), grad(ba, xf - 1.0, yf), u);
+        let x2 = lerp(grad(ab, xf, yf - 1.0), grad(bb, xf - 1.0, yf - 1.0), u);
+        lerp(x1, x2, v)
+    }
+}
+
+fn fade(t: f32) -> f32 { t * t * t * (t * (t * 6.0 - 15.0) + 10.0) }
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 { a + (b - a) * t }
+
+fn grad(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
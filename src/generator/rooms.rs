@@ -0,0 +1,108 @@
+use crate::generator::{fill, fill_rect, seeded_rng, MapGenerator};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+
+/// Rectangular rooms joined in placement order by L-shaped corridors.
+#[derive(Debug, Clone)]
+pub struct RoomsGenerator {
+    pub max_rooms: usize,
+    pub min_room_size: usize,
+    pub max_room_size: usize,
+    pub corridor_width: usize,
+    /// Placement attempts per room before giving up on it.
+    pub max_attempts: usize,
+}
+
+impl Default for RoomsGenerator {
+    fn default() -> Self {
+        Self {
+            max_rooms: 10,
+            min_room_size: 3,
+            max_room_size: 8,
+            corridor_width: 1,
+            max_attempts: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Room {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Room {
+    fn center(&self) -> (usize, usize) { (self.x + self.w / 2, self.y + self.h / 2) }
+
+    /// Overlap test keeping at least one wall cell between rooms.
+    fn intersects(&self, other: &Room) -> bool {
+        self.x <= other.x + other.w && other.x <= self.x + self.w && self.y <= other.y + other.h && other.y <= self.y + self.h
+    }
+}
+
+impl MapGenerator for RoomsGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap {
+        let rng = seeded_rng(seed);
+        let mut grid_map = GridMap::new(config.clone());
+        fill(&mut grid_map, GridNodeValue::Obstacle);
+
+        let width = grid_map.width();
+        let height = grid_map.height();
+        let min_size = self.min_room_size.max(1);
+        let max_size = self.max_room_size.max(min_size);
+        if width < min_size + 2 || height < min_size + 2 {
+            return grid_map;
+        }
+
+        let mut rooms: Vec<Room> = Vec::new();
+        for _ in 0..self.max_rooms {
+            for _ in 0..self.max_attempts {
+                let w = rng.gen_range(min_size, max_size.min(width - 2) + 1);
+                let h = rng.gen_range(min_size, max_size.min(height - 2) + 1);
+                let room = Room {
+                    x: rng.gen_range(1, width - w),
+                    y: rng.gen_range(1, height - h),
+                    w,
+                    h,
+                };
+
+                if rooms.iter().all(|other| !room.intersects(other)) {
+                    rooms.push(room);
+                    break;
+                }
+            }
+        }
+
+        for room in rooms.iter() {
+            fill_rect(&mut grid_map, room.x, room.y, room.w, room.h, GridNodeValue::Air);
+        }
+
+        let corridor = self.corridor_width.max(1);
+        for pair in rooms.windows(2) {
+            let (x0, y0) = pair[0].center();
+            let (x1, y1) = pair[1].center();
+
+            // Randomly bend horizontally or vertically first
+            let (corner_x, corner_y) = if rng.gen_range(0, 2) == 0 { (x1, y0) } else { (x0, y1) };
+            carve_line(&mut grid_map, (x0, y0), (corner_x, corner_y), corridor);
+            carve_line(&mut grid_map, (corner_x, corner_y), (x1, y1), corridor);
+        }
+
+        grid_map
+    }
+}
+
+fn carve_line(grid_map: &mut GridMap, from: (usize, usize), to: (usize, usize), corridor: usize) {
+    let (x0, x1) = (from.0.min(to.0), from.0.max(to.0));
+    let (y0, y1) = (from.1.min(to.1), from.1.max(to.1));
+    let max_x = grid_map.width().saturating_sub(1);
+    let max_y = grid_map.height().saturating_sub(1);
+
+    for y in y0..=(y1 + corridor - 1).min(max_y.saturating_sub(1)) {
+        for x in x0..=(x1 + corridor - 1).min(max_x.saturating_sub(1)) {
+            grid_map.grid_mut().set(x, y, GridNodeValue::Air);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::generator::{seeded_rng, set_border, MapGenerator};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::WorldConfig;
+
+/// Random axis aligned blocks dropped until the obstacle density is reached.
+#[derive(Debug, Clone)]
+pub struct ScatterGenerator {
+    /// Target fraction of obstacle cells, border included.
+    pub density: f32,
+    pub min_size: usize,
+    pub max_size: usize,
+    pub border: bool,
+}
+
+impl Default for ScatterGenerator {
+    fn default() -> Self {
+        Self {
+            density: 0.2,
+            min_size: 1,
+            max_size: 4,
+            border: true,
+        }
+    }
+}
+
+impl MapGenerator for ScatterGenerator {
+    fn generate(&self, config: &WorldConfig, seed: u64) -> GridMap {
+        let rng = seeded_rng(seed);
+        let mut grid_map = GridMap::new(config.clone());
+        let width = grid_map.width();
+        let height = grid_map.height();
+        if width == 0 || height == 0 {
+            return grid_map;
+        }
+
+        if self.border {
+            set_border(&mut grid_map);
+        }
+
+        let total = (width * height) as f32;
+        let target = (self.density.clamp(0.0, 1.0) * total).round() as usize;
+        let min_size = self.min_size.max(1);
+        let max_size = self.max_size.max(min_size);
+
+        let mut obstacles = count_obstacles(&grid_map);
+        // Bound the attempts so unreachable densities still terminate
+        let mut attempts = width * height * 4;
+        while obstacles < target && attempts > 0 {
+            attempts -= 1;
+
+            let w = rng.gen_range(min_size, max_size + 1).min(width);
+            let h = rng.gen_range(min_size, max_size + 1).min(height);
+            let x = rng.gen_range(0, width - w + 1);
+            let y = rng.gen_range(0, height - h + 1);
+
+            for cy in y..y + h {
+                for cx in x..x + w {
+                    if grid_map.grid().is_air(cx, cy) == Some(true) {
+                        grid_map.grid_mut().set(cx, cy, GridNodeValue::Obstacle);
+                        obstacles += 1;
+                    }
+                }
+            }
+        }
+
+        grid_map
+    }
+}
+
+fn count_obstacles(grid_map: &GridMap) -> usize {
+    (0..grid_map.height())
+        .flat_map(|y| (0..grid_map.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| grid_map.grid().get(x, y) == Some(GridNodeValue::Obstacle))
+        .count()
+}
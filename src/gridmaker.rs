@@ -1,323 +1,534 @@
 use crate::world::grid::*;
+use crate::world::WorldConfig;
+use glam::Vec2;
 
-pub fn setup_grid(grid_map: &mut GridMap) {
+pub const DEFAULT_CELL_SIZE: f32 = 100.0;
+
+/// Benchmark map built from one of the named layouts.
+pub struct Preset {
+    pub name: &'static str,
+    pub grid_map: GridMap,
+    /// Start/goal pairs in world coordinates known to be interesting on this layout.
+    pub suggested_queries: Vec<(Vec2, Vec2)>,
+}
+
+type CellQuery = ((usize, usize), (usize, usize));
+
+struct PresetDef {
+    name: &'static str,
+    description: &'static str,
+    /// Size the layout was designed at, presets can be scaled up from it but not down.
+    design_size: (usize, usize),
+    paint: fn(&mut Painter),
+    /// Start/goal pairs as design cells.
+    queries: &'static [CellQuery],
+}
+
+const PRESETS: [PresetDef; 4] = [
+    PresetDef {
+        name: "empty",
+        description: "Outer walls only",
+        design_size: (25, 25),
+        paint: empty,
+        queries: &[((2, 2), (22, 22)), ((2, 22), (22, 2))],
+    },
+    PresetDef {
+        name: "corridors",
+        description: "Four wall stubs splitting the map into loosely connected quarters",
+        design_size: (25, 25),
+        paint: corridors,
+        queries: &[((2, 2), (22, 22)), ((2, 22), (22, 2)), ((2, 12), (22, 12))],
+    },
+    PresetDef {
+        name: "spiral",
+        description: "Concentric rings with alternating gaps, cross corridors and shortcut blockers",
+        design_size: (25, 25),
+        paint: spiral,
+        queries: &[((1, 1), (12, 12)), ((1, 23), (12, 12)), ((23, 1), (1, 23))],
+    },
+    PresetDef {
+        name: "rooms",
+        description: "Room dividers with offset gaps, diagonal barriers and strategic blocks",
+        design_size: (36, 36),
+        paint: rooms,
+        queries: &[((2, 2), (33, 33)), ((33, 2), (2, 33)), ((2, 18), (33, 18))],
+    },
+];
+
+pub fn preset_names() -> Vec<&'static str> { PRESETS.iter().map(|def| def.name).collect() }
+
+pub fn preset_description(name: &str) -> Option<&'static str> {
+    PRESETS.iter().find(|def| def.name == name).map(|def| def.description)
+}
+
+/// Square preset of `size` cells per side with the default cell size.
+pub fn preset(name: &str, size: usize) -> Option<Preset> {
+    preset_with_config(
+        name,
+        &WorldConfig {
+            grid_size: (size, size),
+            cell_size: DEFAULT_CELL_SIZE,
+        },
+    )
+}
+
+/// Scales the named layout to `config.grid_size`.
+/// `None` if the name is unknown or the grid is smaller than the layout's design size.
+pub fn preset_with_config(name: &str, config: &WorldConfig) -> Option<Preset> {
+    let def = PRESETS.iter().find(|def| def.name == name)?;
+    if config.grid_size.0 < def.design_size.0 || config.grid_size.1 < def.design_size.1 {
+        return None;
+    }
+
+    let mut grid_map = GridMap::new(config.clone());
+    let mut painter = Painter {
+        grid_map: &mut grid_map,
+        design_size: def.design_size,
+    };
+    (def.paint)(&mut painter);
+
+    let suggested_queries = def
+        .queries
+        .iter()
+        .map(|&(start, goal)| (painter.center(start.0, start.1), painter.center(goal.0, goal.1)))
+        .collect();
+
+    Some(Preset {
+        name: def.name,
+        grid_map,
+        suggested_queries,
+    })
+}
+
+/// Paints in design cells, each one stretched over the matching block of grid cells.
+struct Painter<'a> {
+    grid_map: &'a mut GridMap,
+    design_size: (usize, usize),
+}
+
+impl Painter<'_> {
+    fn cell_range(&self, x: usize, y: usize) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let (width, height) = (self.grid_map.width(), self.grid_map.height());
+        let (design_width, design_height) = self.design_size;
+        (
+            x * width / design_width..(x + 1) * width / design_width,
+            y * height / design_height..(y + 1) * height / design_height,
+        )
+    }
+
+    fn set(&mut self, x: usize, y: usize, val: GridNodeValue) {
+        let (xs, ys) = self.cell_range(x, y);
+        for gy in ys {
+            for gx in xs.clone() {
+                self.grid_map.grid_mut().set(gx, gy, val);
+            }
+        }
+    }
+
+    fn center(&self, x: usize, y: usize) -> Vec2 {
+        let (xs, ys) = self.cell_range(x, y);
+        let cell_size = self.grid_map.cell_size();
+        Vec2::new(
+            (xs.start + xs.end) as f32 * 0.5 * cell_size,
+            (ys.start + ys.end) as f32 * 0.5 * cell_size,
+        )
+    }
+}
+
+fn empty(p: &mut Painter) {
+    let width = 25;
+    let height = 25;
+
+    for x in 0..width {
+        p.set(x, 0, GridNodeValue::Obstacle);
+        p.set(x, height - 1, GridNodeValue::Obstacle);
+    }
+    for y in 0..height {
+        p.set(0, y, GridNodeValue::Obstacle);
+        p.set(width - 1, y, GridNodeValue::Obstacle);
+    }
+}
+
+fn corridors(p: &mut Painter) {
     let width = 25;
     let height = 25;
 
     for x in 0..width {
-        grid_map.grid_mut().set(x, 0, GridNodeValue::Obstacle);
-        grid_map.grid_mut().set(x, height - 1, GridNodeValue::Obstacle);
+        p.set(x, 0, GridNodeValue::Obstacle);
+        p.set(x, height - 1, GridNodeValue::Obstacle);
     }
     for y in 0..height {
-        grid_map.grid_mut().set(0, y, GridNodeValue::Obstacle);
-        grid_map.grid_mut().set(width - 1, y, GridNodeValue::Obstacle);
+        p.set(0, y, GridNodeValue::Obstacle);
+        p.set(width - 1, y, GridNodeValue::Obstacle);
     }
 
     for x in 0..6 {
-        grid_map.grid_mut().set(x, 10, GridNodeValue::Obstacle);
+        p.set(x, 10, GridNodeValue::Obstacle);
     }
     for x in 18..25 {
-        grid_map.grid_mut().set(x, 14, GridNodeValue::Obstacle);
+        p.set(x, 14, GridNodeValue::Obstacle);
     }
 
     for y in 0..6 {
-        grid_map.grid_mut().set(10, y, GridNodeValue::Obstacle);
+        p.set(10, y, GridNodeValue::Obstacle);
     }
     for y in 12..25 {
-        grid_map.grid_mut().set(13, y, GridNodeValue::Obstacle);
-    }
-
-    // // === OUTER WALLS ===
-    // for x in 0..width {
-    //     grid_map.grid_mut().set(x, 0, GridNodeValue::Obstacle);
-    //     grid_map.grid_mut().set(x, height - 1, GridNodeValue::Obstacle);
-    // }
-    // for y in 0..height {
-    //     grid_map.grid_mut().set(0, y, GridNodeValue::Obstacle);
-    //     grid_map.grid_mut().set(width - 1, y, GridNodeValue::Obstacle);
-    // }
-    //
-    // // === OUTER SPIRAL (Clockwise from outside) ===
-    // // Top horizontal
-    // for x in 2..23 {
-    //     grid_map.grid_mut().set(x, 2, GridNodeValue::Obstacle);
-    // }
-    // // Right vertical
-    // for y in 2..23 {
-    //     grid_map.grid_mut().set(22, y, GridNodeValue::Obstacle);
-    // }
-    // // Bottom horizontal
-    // for x in 2..23 {
-    //     grid_map.grid_mut().set(x, 22, GridNodeValue::Obstacle);
-    // }
-    // // Left vertical
-    // for y in 2..23 {
-    //     grid_map.grid_mut().set(2, y, GridNodeValue::Obstacle);
-    // }
-    // // Gap to enter outer ring
-    // grid_map.grid_mut().set(2, 12, GridNodeValue::Air);
-    //
-    // // === SECOND RING SPIRAL ===
-    // // Top horizontal
-    // for x in 4..21 {
-    //     grid_map.grid_mut().set(x, 4, GridNodeValue::Obstacle);
-    // }
-    // // Right vertical
-    // for y in 4..21 {
-    //     grid_map.grid_mut().set(20, y, GridNodeValue::Obstacle);
-    // }
-    // // Bottom horizontal
-    // for x in 4..21 {
-    //     grid_map.grid_mut().set(x, 20, GridNodeValue::Obstacle);
-    // }
-    // // Left vertical
-    // for y in 4..21 {
-    //     grid_map.grid_mut().set(4, y, GridNodeValue::Obstacle);
-    // }
-    // // Gap to enter second ring
-    // grid_map.grid_mut().set(20, 12, GridNodeValue::Air);
-    //
-    // // === THIRD RING SPIRAL ===
-    // // Top horizontal
-    // for x in 6..19 {
-    //     grid_map.grid_mut().set(x, 6, GridNodeValue::Obstacle);
-    // }
-    // // Right vertical
-    // for y in 6..19 {
-    //     grid_map.grid_mut().set(18, y, GridNodeValue::Obstacle);
-    // }
-    // // Bottom horizontal
-    // for x in 6..19 {
-    //     grid_map.grid_mut().set(x, 18, GridNodeValue::Obstacle);
-    // }
-    // // Left vertical
-    // for y in 6..19 {
-    //     grid_map.grid_mut().set(6, y, GridNodeValue::Obstacle);
-    // }
-    // // Gap to enter third ring
-    // grid_map.grid_mut().set(6, 12, GridNodeValue::Air);
-    //
-    // // === FOURTH RING SPIRAL ===
-    // // Top horizontal
-    // for x in 8..17 {
-    //     grid_map.grid_mut().set(x, 8, GridNodeValue::Obstacle);
-    // }
-    // // Right vertical
-    // for y in 8..17 {
-    //     grid_map.grid_mut().set(16, y, GridNodeValue::Obstacle);
-    // }
-    // // Bottom horizontal
-    // for x in 8..17 {
-    //     grid_map.grid_mut().set(x, 16, GridNodeValue::Obstacle);
-    // }
-    // // Left vertical
-    // for y in 8..17 {
-    //     grid_map.grid_mut().set(8, y, GridNodeValue::Obstacle);
-    // }
-    // // Gap to enter fourth ring
-    // grid_map.grid_mut().set(16, 12, GridNodeValue::Air);
-    //
-    // // === INNERMOST SPIRAL ===
-    // // Top horizontal
-    // for x in 10..15 {
-    //     grid_map.grid_mut().set(x, 10, GridNodeValue::Obstacle);
-    // }
-    // // Right vertical
-    // for y in 10..15 {
-    //     grid_map.grid_mut().set(14, y, GridNodeValue::Obstacle);
-    // }
-    // // Bottom horizontal
-    // for x in 10..15 {
-    //     grid_map.grid_mut().set(x, 14, GridNodeValue::Obstacle);
-    // }
-    // // Left vertical (partial)
-    // for y in 10..14 {
-    //     grid_map.grid_mut().set(10, y, GridNodeValue::Obstacle);
-    // }
-    // // Gap to center
-    // grid_map.grid_mut().set(10, 12, GridNodeValue::Air);
-    //
-    // // === CROSS CORRIDORS (Creates alternative paths and loops) ===
-    // // Horizontal corridor through middle
-    // for x in 2..23 {
-    //     if x != 4 && x != 6 && x != 8 && x != 10 && x != 14 && x != 16 && x != 18 && x != 20 {
-    //         grid_map.grid_mut().set(x, 12, GridNodeValue::Air);
-    //     }
-    // }
-    //
-    // // Vertical corridor creating more complexity
-    // for y in 6..19 {
-    //     if y == 9 || y == 15 {
-    //         grid_map.grid_mut().set(12, y, GridNodeValue::Air);
-    //     }
-    // }
-    //
-    // // === ADDITIONAL BARRIERS TO FORCE LONGER PATHS ===
-    // // Block some shortcut paths
-    // for y in 3..7 {
-    //     grid_map.grid_mut().set(12, y, GridNodeValue::Obstacle);
-    // }
-    //
-    // for y in 18..22 {
-    //     grid_map.grid_mut().set(12, y, GridNodeValue::Obstacle);
-    // }
-    //
-    // for x in 3..7 {
-    //     grid_map.grid_mut().set(x, 8, GridNodeValue::Obstacle);
-    // }
-    //
-    // for x in 18..22 {
-    //     grid_map.grid_mut().set(x, 16, GridNodeValue::Obstacle);
-    // }
-    //
-    // grid_map.grid_mut().set(10, 2, GridNodeValue::Air);
-    // grid_map.grid_mut().set(5, 8, GridNodeValue::Air);
-    // grid_map.grid_mut().set(15, 6, GridNodeValue::Air);
-    // grid_map.grid_mut().set(12, 21, GridNodeValue::Air);
-    // grid_map.grid_mut().set(20, 17, GridNodeValue::Air);
+        p.set(13, y, GridNodeValue::Obstacle);
+    }
+}
+
+fn spiral(p: &mut Painter) {
+    let width = 25;
+    let height = 25;
+
+    // === OUTER WALLS ===
+    for x in 0..width {
+        p.set(x, 0, GridNodeValue::Obstacle);
+        p.set(x, height - 1, GridNodeValue::Obstacle);
+    }
+    for y in 0..height {
+        p.set(0, y, GridNodeValue::Obstacle);
+        p.set(width - 1, y, GridNodeValue::Obstacle);
+    }
+
+    // === OUTER SPIRAL (Clockwise from outside) ===
+    // Top horizontal
+    for x in 2..23 {
+        p.set(x, 2, GridNodeValue::Obstacle);
+    }
+    // Right vertical
+    for y in 2..23 {
+        p.set(22, y, GridNodeValue::Obstacle);
+    }
+    // Bottom horizontal
+    for x in 2..23 {
+        p.set(x, 22, GridNodeValue::Obstacle);
+    }
+    // Left vertical
+    for y in 2..23 {
+        p.set(2, y, GridNodeValue::Obstacle);
+    }
+    // Gap to enter outer ring
+    p.set(2, 12, GridNodeValue::Air);
+
+    // === SECOND RING SPIRAL ===
+    // Top horizontal
+    for x in 4..21 {
+        p.set(x, 4, GridNodeValue::Obstacle);
+    }
+    // Right vertical
+    for y in 4..21 {
+        p.set(20, y, GridNodeValue::Obstacle);
+    }
+    // Bottom horizontal
+    for x in 4..21 {
+        p.set(x, 20, GridNodeValue::Obstacle);
+    }
+    // Left vertical
+    for y in 4..21 {
+        p.set(4, y, GridNodeValue::Obstacle);
+    }
+    // Gap to enter second ring
+    p.set(20, 12, GridNodeValue::Air);
+
+    // === THIRD RING SPIRAL ===
+    // Top horizontal
+    for x in 6..19 {
+        p.set(x, 6, GridNodeValue::Obstacle);
+    }
+    // Right vertical
+    for y in 6..19 {
+        p.set(18, y, GridNodeValue::Obstacle);
+    }
+    // Bottom horizontal
+    for x in 6..19 {
+        p.set(x, 18, GridNodeValue::Obstacle);
+    }
+    // Left vertical
+    for y in 6..19 {
+        p.set(6, y, GridNodeValue::Obstacle);
+    }
+    // Gap to enter third ring
+    p.set(6, 12, GridNodeValue::Air);
+
+    // === FOURTH RING SPIRAL ===
+    // Top horizontal
+    for x in 8..17 {
+        p.set(x, 8, GridNodeValue::Obstacle);
+    }
+    // Right vertical
+    for y in 8..17 {
+        p.set(16, y, GridNodeValue::Obstacle);
+    }
+    // Bottom horizontal
+    for x in 8..17 {
+        p.set(x, 16, GridNodeValue::Obstacle);
+    }
+    // Left vertical
+    for y in 8..17 {
+        p.set(8, y, GridNodeValue::Obstacle);
+    }
+    // Gap to enter fourth ring
+    p.set(16, 12, GridNodeValue::Air);
+
+    // === INNERMOST SPIRAL ===
+    // Top horizontal
+    for x in 10..15 {
+        p.set(x, 10, GridNodeValue::Obstacle);
+    }
+    // Right vertical
+    for y in 10..15 {
+        p.set(14, y, GridNodeValue::Obstacle);
+    }
+    // Bottom horizontal
+    for x in 10..15 {
+        p.set(x, 14, GridNodeValue::Obstacle);
+    }
+    // Left vertical (partial)
+    for y in 10..14 {
+        p.set(10, y, GridNodeValue::Obstacle);
+    }
+    // Gap to center
+    p.set(10, 12, GridNodeValue::Air);
+
+    // === CROSS CORRIDORS (Creates alternative paths and loops) ===
+    // Horizontal corridor through middle
+    for x in 2..23 {
+        if x != 4 && x != 6 && x != 8 && x != 10 && x != 14 && x != 16 && x != 18 && x != 20 {
+            p.set(x, 12, GridNodeValue::Air);
+        }
+    }
+
+    // Vertical corridor creating more complexity
+    for y in 6..19 {
+        if y == 9 || y == 15 {
+            p.set(12, y, GridNodeValue::Air);
+        }
+    }
+
+    // === ADDITIONAL BARRIERS TO FORCE LONGER PATHS ===
+    // Block some shortcut paths
+    for y in 3..7 {
+        p.set(12, y, GridNodeValue::Obstacle);
+    }
+
+    for y in 18..22 {
+        p.set(12, y, GridNodeValue::Obstacle);
+    }
+
+    for x in 3..7 {
+        p.set(x, 8, GridNodeValue::Obstacle);
+    }
+
+    for x in 18..22 {
+        p.set(x, 16, GridNodeValue::Obstacle);
+    }
+
+    p.set(10, 2, GridNodeValue::Air);
+    p.set(5, 8, GridNodeValue::Air);
+    p.set(15, 6, GridNodeValue::Air);
+    p.set(12, 21, GridNodeValue::Air);
+    p.set(20, 17, GridNodeValue::Air);
+}
+
+fn rooms(p: &mut Painter) {
+    let width = 36;
+    let height = 36;
+
+    // === OUTER WALLS ===
+    for x in 0..width {
+        p.set(x, 0, GridNodeValue::Obstacle);
+        p.set(x, height - 1, GridNodeValue::Obstacle);
+    }
+    for y in 0..height {
+        p.set(0, y, GridNodeValue::Obstacle);
+        p.set(width - 1, y, GridNodeValue::Obstacle);
+    }
+
+    // === LARGE ROOM DIVIDERS ===
+    // Vertical divider with gaps
+    for y in 5..31 {
+        if !(12..=18).contains(&y) {
+            // Gap in middle
+            p.set(12, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    for y in 5..31 {
+        if !(15..=21).contains(&y) {
+            // Gap in middle (offset from first)
+            p.set(24, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    // Horizontal divider with gaps
+    for x in 5..31 {
+        if !(15..=21).contains(&x) {
+            // Gap in middle
+            p.set(x, 12, GridNodeValue::Obstacle);
+        }
+    }
+
+    for x in 5..31 {
+        if !(12..=18).contains(&x) {
+            // Gap in middle (offset from first)
+            p.set(x, 24, GridNodeValue::Obstacle);
+        }
+    }
+
+    // === DIAGONAL BARRIERS ===
+    // Top-left to center
+    for i in 0..8 {
+        p.set(5 + i, 5 + i, GridNodeValue::Obstacle);
+        p.set(4 + i, 5 + i, GridNodeValue::Obstacle);
+    }
+
+    // Top-right to center
+    for i in 0..8 {
+        p.set(30 - i, 5 + i, GridNodeValue::Obstacle);
+        p.set(29 - i, 5 + i, GridNodeValue::Obstacle);
+    }
+
+    // Bottom-left to center
+    for i in 0..8 {
+        p.set(5 + i, 30 - i, GridNodeValue::Obstacle);
+        p.set(4 + i, 30 - i, GridNodeValue::Obstacle);
+    }
+
+    // Bottom-right to center
+    for i in 0..8 {
+        p.set(30 - i, 30 - i, GridNodeValue::Obstacle);
+        p.set(29 - i, 30 - i, GridNodeValue::Obstacle);
+    }
+
+    // === CENTRAL OBSTACLE ===
+    for x in 17..19 {
+        for y in 17..19 {
+            p.set(x, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    for x in 10..21 {
+        p.set(x, 15, GridNodeValue::Obstacle);
+    }
+    for y in 16..20 {
+        p.set(15, y, GridNodeValue::Obstacle);
+    }
+    for x in 14..22 {
+        p.set(x, 21, GridNodeValue::Obstacle);
+    }
+    for y in 16..20 {
+        p.set(22, y, GridNodeValue::Obstacle);
+    }
+
+    for x in 23..30 {
+        p.set(x, 16, GridNodeValue::Obstacle);
+    }
+
+    // === CORNER OBSTACLES ===
+    // Top-left
+    for x in 3..6 {
+        for y in 3..6 {
+            p.set(x, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    // Top-right
+    for x in 30..33 {
+        for y in 3..6 {
+            p.set(x, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    // Bottom-left
+    for x in 3..6 {
+        for y in 30..33 {
+            p.set(x, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    // Bottom-right
+    for x in 30..33 {
+        for y in 30..33 {
+            p.set(x, y, GridNodeValue::Obstacle);
+        }
+    }
+
+    // === STRATEGIC BLOCKS ===
+    // These force interesting paths without too many vertices
+    let blocks = [
+        (8, 8, 2, 2),
+        (26, 8, 2, 2),
+        (8, 26, 2, 2),
+        (26, 26, 2, 2),
+        (18, 8, 2, 2),
+        (8, 18, 2, 2),
+        (26, 18, 2, 2),
+        (18, 26, 2, 2),
+    ];
+
+    for (x, y, w, h) in blocks.iter() {
+        for dx in 0..*w {
+            for dy in 0..*h {
+                p.set(x + dx, y + dy, GridNodeValue::Obstacle);
+            }
+        }
+    }
 }
 
-// pub fn setup_grid(grid_map: &mut GridMap) {
-//     let width = 36;
-//     let height = 36;
-//
-//     // === OUTER WALLS ===
-//     for x in 0..width {
-//         grid_map.grid_mut().set(x, 0, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(x, height - 1, GridNodeValue::Obstacle);
-//     }
-//     for y in 0..height {
-//         grid_map.grid_mut().set(0, y, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(width - 1, y, GridNodeValue::Obstacle);
-//     }
-//
-//     // === LARGE ROOM DIVIDERS ===
-//     // Vertical divider with gaps
-//     for y in 5..31 {
-//         if y < 12 || y > 18 {
-//             // Gap in middle
-//             grid_map.grid_mut().set(12, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     for y in 5..31 {
-//         if y < 15 || y > 21 {
-//             // Gap in middle (offset from first)
-//             grid_map.grid_mut().set(24, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // Horizontal divider with gaps
-//     for x in 5..31 {
-//         if x < 15 || x > 21 {
-//             // Gap in middle
-//             grid_map.grid_mut().set(x, 12, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     for x in 5..31 {
-//         if x < 12 || x > 18 {
-//             // Gap in middle (offset from first)
-//             grid_map.grid_mut().set(x, 24, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // === DIAGONAL BARRIERS ===
-//     // Top-left to center
-//     for i in 0..8 {
-//         grid_map.grid_mut().set(5 + i, 5 + i, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(4 + i, 5 + i, GridNodeValue::Obstacle);
-//     }
-//
-//     // Top-right to center
-//     for i in 0..8 {
-//         grid_map.grid_mut().set(30 - i, 5 + i, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(29 - i, 5 + i, GridNodeValue::Obstacle);
-//     }
-//
-//     // Bottom-left to center
-//     for i in 0..8 {
-//         grid_map.grid_mut().set(5 + i, 30 - i, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(4 + i, 30 - i, GridNodeValue::Obstacle);
-//     }
-//
-//     // Bottom-right to center
-//     for i in 0..8 {
-//         grid_map.grid_mut().set(30 - i, 30 - i, GridNodeValue::Obstacle);
-//         grid_map.grid_mut().set(29 - i, 30 - i, GridNodeValue::Obstacle);
-//     }
-//
-//     // === CENTRAL OBSTACLE ===
-//     for x in 17..19 {
-//         for y in 17..19 {
-//             grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     for x in 10..21 {
-//         grid_map.grid_mut().set(x, 15, GridNodeValue::Obstacle);
-//     }
-//     for y in 16..20 {
-//         grid_map.grid_mut().set(15, y, GridNodeValue::Obstacle);
-//     }
-//     for x in 14..22 {
-//         grid_map.grid_mut().set(x, 21, GridNodeValue::Obstacle);
-//     }
-//     for y in 16..20 {
-//         grid_map.grid_mut().set(22, y, GridNodeValue::Obstacle);
-//     }
-//
-//     for x in 23..30 {
-//         grid_map.grid_mut().set(x, 16, GridNodeValue::Obstacle);
-//     }
-//
-//     // === CORNER OBSTACLES ===
-//     // Top-left
-//     for x in 3..6 {
-//         for y in 3..6 {
-//             grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // Top-right
-//     for x in 30..33 {
-//         for y in 3..6 {
-//             grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // Bottom-left
-//     for x in 3..6 {
-//         for y in 30..33 {
-//             grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // Bottom-right
-//     for x in 30..33 {
-//         for y in 30..33 {
-//             grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
-//         }
-//     }
-//
-//     // === STRATEGIC BLOCKS ===
-//     // These force interesting paths without too many vertices
-//     let blocks = [
-//         (8, 8, 2, 2),
-//         (26, 8, 2, 2),
-//         (8, 26, 2, 2),
-//         (26, 26, 2, 2),
-//         (18, 8, 2, 2),
-//         (8, 18, 2, 2),
-//         (26, 18, 2, 2),
-//         (18, 26, 2, 2),
-//     ];
-//
-//     for (x, y, w, h) in blocks.iter() {
-//         for dx in 0..*w {
-//             for dy in 0..*h {
-//                 grid_map.grid_mut().set(x + dx, y + dy, GridNodeValue::Obstacle);
-//             }
-//         }
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Free cells four-connected to the cell holding `from`.
+    fn reachable_cells(grid_map: &GridMap, from: Vec2) -> Vec<bool> {
+        let (width, height) = (grid_map.width(), grid_map.height());
+        let mut reached = vec![false; width * height];
+        let Some(start) = grid_map.world_to_cell(from) else {
+            return reached;
+        };
+
+        let mut queue = VecDeque::from([start]);
+        reached[start.1 * width + start.0] = true;
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbours = [(x + 1, y), (x.wrapping_sub(1), y), (x, y + 1), (x, y.wrapping_sub(1))];
+            for (nx, ny) in neighbours {
+                if grid_map.grid().is_air(nx, ny) == Some(true) && !reached[ny * width + nx] {
+                    reached[ny * width + nx] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        reached
+    }
+
+    #[test]
+    fn every_preset_suggests_queries_in_connected_free_space() {
+        for name in preset_names() {
+            for size in [36, 50] {
+                let preset = preset(name, size).unwrap_or_else(|| panic!("{} is listed but does not build", name));
+                assert_eq!(preset.name, name);
+                assert!(preset_description(name).is_some());
+                assert!(!preset.suggested_queries.is_empty(), "{} suggests no queries", name);
+
+                let grid_map = &preset.grid_map;
+                for &(start, goal) in &preset.suggested_queries {
+                    for pos in [start, goal] {
+                        let (x, y) = grid_map.world_to_cell(pos).expect("queries lie inside the map");
+                        assert_eq!(grid_map.grid().is_air(x, y), Some(true), "{} at {} in {}", pos, size, name);
+                    }
+                    let (x, y) = grid_map.world_to_cell(goal).unwrap();
+                    let reached = reachable_cells(grid_map, start);
+                    assert!(reached[y * grid_map.width() + x], "{} to {} disconnected in {}", start, goal, name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_or_too_small_presets_are_rejected() {
+        assert!(preset("no such map", 50).is_none());
+        assert!(preset("rooms", 20).is_none());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod algorithm;
+#[cfg(feature = "render")]
 pub mod game;
+pub mod generator;
 pub mod gridmaker;
 pub mod world;
 
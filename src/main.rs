@@ -1,12 +1,11 @@
 use macroquad::prelude::*;
+use pathfinding::algorithm::registry::PlannerConfig;
 use pathfinding::game::camera::*;
 use pathfinding::game::game::*;
 use pathfinding::game::map_renderer::*;
 use pathfinding::game::path_renderer::*;
 use pathfinding::game::ui::*;
-use pathfinding::gridmaker::setup_grid;
-use pathfinding::world::WorldConfig;
-use pathfinding::world::grid::*;
+use pathfinding::gridmaker;
 use std::sync::Arc;
 
 fn window_conf() -> Conf {
@@ -20,9 +19,23 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
-    let world_config = WorldConfig {
-        grid_size: (25, 25),
-        cell_size: 100.0,
+    // Usage: pathfinding-demo [preset] [size] [planner config JSON]
+    let preset_name = std::env::args().nth(1).unwrap_or_else(|| "corridors".to_string());
+    let preset_size = std::env::args().nth(2).and_then(|arg| arg.parse().ok()).unwrap_or(25);
+    let planner_config = match std::env::args().nth(3) {
+        Some(path) => {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| PlannerConfig::from_json(&text).map_err(|err| err.to_string()));
+            match parsed {
+                Ok(config) => config,
+                Err(err) => {
+                    std::println!("Cannot load planner config '{}': {}", path, err);
+                    return;
+                }
+            }
+        }
+        None => PlannerConfig::default(),
     };
 
     let render_config = RenderConfig {
@@ -38,8 +51,16 @@ async fn main() {
     };
 
     // ==================================================================
-    let mut grid_map = GridMap::new(world_config.clone());
-    setup_grid(&mut grid_map);
+    let Some(preset) = gridmaker::preset(&preset_name, preset_size) else {
+        std::println!(
+            "Unknown preset '{}' or size {} too small, available: {}",
+            preset_name,
+            preset_size,
+            gridmaker::preset_names().join(", ")
+        );
+        return;
+    };
+    let grid_map = preset.grid_map;
 
     let mut game_manager = {
         let mut map_renderer = MapRenderer::new(render_config.clone());
@@ -57,14 +78,20 @@ async fn main() {
         let ui_manager = UIManager::new(render_config.font_size);
         let grid_map_arc = Arc::new(grid_map);
 
-        GameManager::new(
+        let mut game_manager = GameManager::new(
             Box::new(map_renderer),
             Box::new(path_renderer),
             Box::new(camera_manager),
             Box::new(ui_manager),
             render_config,
             grid_map_arc,
-        )
+        );
+        game_manager.set_planner_config(planner_config);
+        if let Some(&(start, end)) = preset.suggested_queries.first() {
+            game_manager.set_start_pos(start);
+            game_manager.set_end_pos(end);
+        }
+        game_manager
     };
     // ==================================================================
     // let test_case = 15;
@@ -0,0 +1,307 @@
+use crate::world::clearance::ClearanceField;
+use crate::world::grid::GridMap;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WidthBin {
+    /// Corridor width rounded to whole cells.
+    pub width_cells: usize,
+    pub count: usize,
+}
+
+/// Corridor widths sampled on the ridge of the clearance field, in world units.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CorridorWidthStats {
+    pub samples: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub median: f32,
+    pub histogram: Vec<WidthBin>,
+}
+
+/// Structural features of a map, meant to be correlated with planner performance.
+#[derive(Debug, Clone, Serialize)]
+pub struct MapAnalysis {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+    pub obstacle_density: f32,
+    pub free_cells: usize,
+    /// 4-connected regions of free cells.
+    pub free_components: usize,
+    pub largest_component: usize,
+    pub corridor_width: CorridorWidthStats,
+    /// Free cells with a single free 4-neighbour.
+    pub dead_ends: Vec<(usize, usize)>,
+    /// Free cells whose removal disconnects their region.
+    pub chokepoints: Vec<(usize, usize)>,
+}
+
+impl MapAnalysis {
+    pub fn new(grid_map: &GridMap) -> Self {
+        let width = grid_map.width();
+        let height = grid_map.height();
+        let free: Vec<bool> = (0..width * height)
+            .map(|i| grid_map.grid().is_air(i % width, i / width) == Some(true))
+            .collect();
+        let free_cells = free.iter().filter(|&&is_free| is_free).count();
+
+        let (free_components, largest_component) = count_components(&free, width, height);
+        let clearance = ClearanceField::new(grid_map);
+
+        let dead_ends = (0..width * height)
+            .filter(|&i| free[i] && free_neighbours(&free, width, height, i).count() == 1)
+            .map(|i| (i % width, i / width))
+            .collect();
+
+        let chokepoints = articulation_points(&free, width, height)
+            .into_iter()
+            .map(|i| (i % width, i / width))
+            .collect();
+
+        Self {
+            width,
+            height,
+            cell_size: grid_map.cell_size(),
+            obstacle_density: if width * height == 0 {
+                0.0
+            } else {
+                1.0 - free_cells as f32 / (width * height) as f32
+            },
+            free_cells,
+            free_components,
+            largest_component,
+            corridor_width: corridor_widths(&clearance, &free, grid_map.cell_size()),
+            dead_ends,
+            chokepoints,
+        }
+    }
+
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).unwrap_or_default() }
+}
+
+impl GridMap {
+    pub fn analyze(&self) -> MapAnalysis { MapAnalysis::new(self) }
+}
+
+fn free_neighbours(free: &[bool], width: usize, height: usize, index: usize) -> impl Iterator<Item = usize> + '_ {
+    let x = index % width;
+    let y = index / width;
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .filter_map(move |(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (nx < width && ny < height).then_some(ny * width + nx)
+        })
+        .filter(move |&n| free[n])
+}
+
+fn count_components(free: &[bool], width: usize, height: usize) -> (usize, usize) {
+    let mut seen = vec![false; free.len()];
+    let mut components = 0;
+    let mut largest = 0;
+
+    for start in 0..free.len() {
+        if !free[start] || seen[start] {
+            continue;
+        }
+
+        components += 1;
+        let mut size = 0;
+        let mut stack = vec![start];
+        seen[start] = true;
+        while let Some(cur) = stack.pop() {
+            size += 1;
+            for next in free_neighbours(free, width, height, cur) {
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        largest = largest.max(size);
+    }
+
+    (components, largest)
+}
+
+/// Width samples are taken on cells that are a clearance maximum along a row or column.
+fn corridor_widths(clearance: &ClearanceField, free: &[bool], cell_size: f32) -> CorridorWidthStats {
+    let width = clearance.width();
+    let height = clearance.height();
+    let dist_at = |x: isize, y: isize| {
+        if x < 0 || y < 0 {
+            return 0.0;
+        }
+        clearance.cell_distance(x as usize, y as usize).unwrap_or(0.0)
+    };
+
+    let mut widths: Vec<f32> = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !free[y * width + x] {
+                continue;
+            }
+
+            let (ix, iy) = (x as isize, y as isize);
+            let dist = dist_at(ix, iy);
+            let ridge_x = dist >= dist_at(ix - 1, iy) && dist >= dist_at(ix + 1, iy);
+            let ridge_y = dist >= dist_at(ix, iy - 1) && dist >= dist_at(ix, iy + 1);
+            if ridge_x || ridge_y {
+                // Free span across the ridge is twice the gap to the closest wall boundary
+                widths.push((2.0 * dist - 1.0).max(1.0) * cell_size);
+            }
+        }
+    }
+
+    if widths.is_empty() {
+        return CorridorWidthStats::default();
+    }
+
+    widths.sort_by(f32::total_cmp);
+    let mut histogram: Vec<WidthBin> = Vec::new();
+    for width in widths.iter() {
+        let width_cells = (width / cell_size).round() as usize;
+        match histogram.last_mut() {
+            Some(bin) if bin.width_cells == width_cells => bin.count += 1,
+            _ => histogram.push(WidthBin { width_cells, count: 1 }),
+        }
+    }
+
+    CorridorWidthStats {
+        samples: widths.len(),
+        min: widths[0],
+        max: widths[widths.len() - 1],
+        mean: widths.iter().sum::<f32>() / widths.len() as f32,
+        median: widths[widths.len() / 2],
+        histogram,
+    }
+}
+
+/// Iterative Tarjan over the 4-connected free cell graph.
+fn articulation_points(free: &[bool], width: usize, height: usize) -> Vec<usize> {
+    let count = free.len();
+    let mut discovery = vec![usize::MAX; count];
+    let mut low = vec![0; count];
+    let mut is_articulation = vec![false; count];
+    let mut timer = 0;
+
+    for root in 0..count {
+        if !free[root] || discovery[root] != usize::MAX {
+            continue;
+        }
+
+        discovery[root] = timer;
+        low[root] = timer;
+        timer += 1;
+        let mut root_children = 0;
+
+        // (cell, parent, neighbours still to visit)
+        let mut stack: Vec<(usize, usize, Vec<usize>)> =
+            vec![(root, usize::MAX, free_neighbours(free, width, height, root).collect())];
+
+        while let Some((cell, parent, pending)) = stack.last_mut() {
+            let (cell, parent) = (*cell, *parent);
+            if let Some(next) = pending.pop() {
+                if next == parent {
+                    continue;
+                }
+
+                if discovery[next] == usize::MAX {
+                    discovery[next] = timer;
+                    low[next] = timer;
+                    timer += 1;
+                    if cell == root {
+                        root_children += 1;
+                    }
+                    stack.push((next, cell, free_neighbours(free, width, height, next).collect()));
+                } else {
+                    low[cell] = low[cell].min(discovery[next]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            if parent != usize::MAX {
+                low[parent] = low[parent].min(low[cell]);
+                if parent != root && low[cell] >= discovery[parent] {
+                    is_articulation[parent] = true;
+                }
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    (0..count).filter(|&i| is_articulation[i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+    use crate::world::grid::GridNodeValue;
+
+    /// Builds a map from rows of `#` obstacles and `.` free cells.
+    fn map(rows: &[&str]) -> GridMap {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (rows[0].len(), rows.len()),
+            cell_size: 10.0,
+        });
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                if cell == '#' {
+                    grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+                }
+            }
+        }
+        grid_map
+    }
+
+    #[test]
+    fn door_between_two_rooms_is_the_only_chokepoint() {
+        let analysis = map(&["...#...", ".......", "...#..."]).analyze();
+        assert_eq!(analysis.free_cells, 19);
+        assert_eq!(analysis.free_components, 1);
+        assert_eq!(analysis.largest_component, 19);
+        assert_eq!(analysis.chokepoints, vec![(2, 1), (3, 1), (4, 1)]);
+        assert!(analysis.dead_ends.is_empty());
+    }
+
+    #[test]
+    fn corridor_has_dead_ends_at_both_ends() {
+        let analysis = map(&["######", ".....#", "######"]).analyze();
+        assert_eq!(analysis.free_cells, 5);
+        assert_eq!(analysis.chokepoints, vec![(1, 1), (2, 1), (3, 1)]);
+        assert_eq!(analysis.dead_ends, vec![(0, 1), (4, 1)]);
+
+        // Every cell of a one cell wide corridor is a ridge sample of width one cell
+        let widths = &analysis.corridor_width;
+        assert_eq!(widths.samples, 5);
+        assert_eq!((widths.min, widths.max), (10.0, 10.0));
+        assert_eq!(widths.histogram.len(), 1);
+        assert_eq!((widths.histogram[0].width_cells, widths.histogram[0].count), (1, 5));
+    }
+
+    #[test]
+    fn loop_has_no_chokepoints() {
+        let analysis = map(&["....", ".##.", "...."]).analyze();
+        assert_eq!(analysis.free_components, 1);
+        assert!(analysis.chokepoints.is_empty());
+        assert!(analysis.dead_ends.is_empty());
+    }
+
+    #[test]
+    fn separated_regions_are_counted() {
+        let analysis = map(&["..#.", "..#.", "..#."]).analyze();
+        assert_eq!(analysis.free_components, 2);
+        assert_eq!(analysis.largest_component, 6);
+        assert_eq!(analysis.chokepoints, vec![(3, 1)]);
+        assert_eq!(analysis.dead_ends, vec![(3, 0), (3, 2)]);
+        assert!((analysis.obstacle_density - 0.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::world::grid::{GridMap, GridNodeValue};
+use glam::Vec2;
+
+/// Euclidean distance from every cell to the closest obstacle, the map border counts as an obstacle.
+#[derive(Debug, Clone)]
+pub struct ClearanceField {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    /// Distance in cells from a cell center to the nearest obstacle cell center.
+    dist: Vec<f32>,
+}
+
+impl ClearanceField {
+    pub fn new(grid_map: &GridMap) -> Self {
+        let width = grid_map.width();
+        let height = grid_map.height();
+
+        // Pad with a ring of obstacles so the world edge behaves like a wall
+        let padded_width = width + 2;
+        let padded_height = height + 2;
+        let mut sq_dist = vec![f32::INFINITY; padded_width * padded_height];
+        for y in 0..padded_height {
+            for x in 0..padded_width {
+                let border = x == 0 || y == 0 || x == padded_width - 1 || y == padded_height - 1;
+                if border || grid_map.grid().get(x - 1, y - 1) == Some(GridNodeValue::Obstacle) {
+                    sq_dist[y * padded_width + x] = 0.0;
+                }
+            }
+        }
+
+        let mut column = vec![0.0; padded_height];
+        for x in 0..padded_width {
+            for y in 0..padded_height {
+                column[y] = sq_dist[y * padded_width + x];
+            }
+            let transformed = distance_transform_1d(&column);
+            for y in 0..padded_height {
+                sq_dist[y * padded_width + x] = transformed[y];
+            }
+        }
+        for y in 0..padded_height {
+            let row = &mut sq_dist[y * padded_width..(y + 1) * padded_width];
+            let transformed = distance_transform_1d(row);
+            row.copy_from_slice(&transformed);
+        }
+
+        let mut dist = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                dist.push(sq_dist[(y + 1) * padded_width + x + 1].sqrt());
+            }
+        }
+
+        Self {
+            width,
+            height,
+            cell_size: grid_map.cell_size(),
+            dist,
+        }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn cell_size(&self) -> f32 { self.cell_size }
+
+    /// Distance in cells between the cell center and the nearest obstacle cell center, zero on obstacles.
+    pub fn cell_distance(&self, x: usize, y: usize) -> Option<f32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.dist[y * self.width + x])
+    }
+
+    /// World space gap between the cell center and the nearest obstacle boundary.
+    pub fn cell_clearance(&self, x: usize, y: usize) -> Option<f32> {
+        self.cell_distance(x, y).map(|dist| ((dist - 0.5) * self.cell_size).max(0.0))
+    }
+
+    /// World space distance from `pos` to the nearest obstacle boundary or the map edge, zero inside obstacles and
+    /// outside the map.
+    pub fn clearance_at(&self, pos: Vec2) -> f32 {
+        let size = Vec2::new(self.width as f32, self.height as f32) * self.cell_size;
+        if !(pos.x >= 0.0 && pos.y >= 0.0 && pos.x <= size.x && pos.y <= size.y) {
+            return 0.0;
+        }
+
+        let x = ((pos.x / self.cell_size) as usize).min(self.width - 1);
+        let y = ((pos.y / self.cell_size) as usize).min(self.height - 1);
+        let dist = self.dist[y * self.width + x];
+        if dist == 0.0 {
+            return 0.0;
+        }
+
+        // The obstacle nearest to the cell center is within `dist` cells, so nothing further than one more cell
+        // can be nearer to a point inside the cell
+        let mut best = pos.x.min(pos.y).min(size.x - pos.x).min(size.y - pos.y);
+        let reach = (dist + 1.0).ceil() as usize;
+        for cy in y.saturating_sub(reach)..(y + reach + 1).min(self.height) {
+            for cx in x.saturating_sub(reach)..(x + reach + 1).min(self.width) {
+                if self.dist[cy * self.width + cx] != 0.0 {
+                    continue;
+                }
+                let cell_min = Vec2::new(cx as f32, cy as f32) * self.cell_size;
+                let gap = (cell_min - pos).max(pos - cell_min - Vec2::splat(self.cell_size)).max(Vec2::ZERO);
+                best = best.min(gap.length());
+            }
+        }
+        best
+    }
+
+    pub fn max_clearance(&self) -> f32 {
+        let max_dist = self.dist.iter().copied().fold(0.0, f32::max);
+        ((max_dist - 0.5) * self.cell_size).max(0.0)
+    }
+}
+
+/// Felzenszwalb-Huttenlocher squared distance transform of a sampled function.
+fn distance_transform_1d(f: &[f32]) -> Vec<f32> {
+    let n = f.len();
+    let mut result = vec![f32::INFINITY; n];
+    let mut hull = vec![0usize; n];
+    let mut bounds = vec![0.0f32; n + 1];
+    let mut k: usize = 0;
+    let mut initialized = false;
+
+    for q in 0..n {
+        if f[q].is_infinite() {
+            continue;
+        }
+
+        if !initialized {
+            hull[0] = q;
+            bounds[0] = f32::NEG_INFINITY;
+            bounds[1] = f32::INFINITY;
+            initialized = true;
+            continue;
+        }
+
+        let intersect = |p: usize| ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * (q as f32 - p as f32));
+        let mut s = intersect(hull[k]);
+        while k > 0 && s <= bounds[k] {
+            k -= 1;
+            s = intersect(hull[k]);
+        }
+        k += 1;
+        hull[k] = q;
+        bounds[k] = s;
+        bounds[k + 1] = f32::INFINITY;
+    }
+
+    if !initialized {
+        return result;
+    }
+
+    k = 0;
+    for (q, value) in result.iter_mut().enumerate() {
+        while bounds[k + 1] < q as f32 {
+            k += 1;
+        }
+        let p = hull[k];
+        let diff = q as f32 - p as f32;
+        *value = diff * diff + f[p];
+    }
+    result
+}
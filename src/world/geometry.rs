@@ -0,0 +1,189 @@
+use crate::world::types::{Quad, Ray, RayHitInfo};
+use crate::world::zone::ZoneLayer;
+use glam::Vec2;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Collision queries shared by every world representation a `Problem` can be built on.
+pub trait Geometry: Send + Sync {
+    /// Axis aligned area covered by the world.
+    fn bounds(&self) -> Quad;
+
+    /// First obstacle hit along `ray`, `None` if the ray leaves the world without hitting anything.
+    fn raycast(&self, ray: Ray) -> Option<RayHitInfo>;
+
+    fn is_point_in_obstacle(&self, pt: Vec2) -> bool;
+
+    /// `true` if a disc of `radius` swept from `from` to `to` never overlaps an obstacle.
+    /// A radius of zero only rejects segments that pass through an obstacle's interior, backends may also reject
+    /// squeezing between obstacles that touch in a single point.
+    fn is_segment_clear(&self, from: Vec2, to: Vec2, radius: f32) -> bool;
+
+    /// Semantic zones layered over the obstacles, if the backend supports them.
+    fn zones(&self) -> Option<&ZoneLayer> { None }
+
+    /// Collision hazard `-ln(1 - p)` accumulated along the segment from uncertain occupancy, if any.
+    fn segment_hazard(&self, _from: Vec2, _to: Vec2) -> f64 { 0.0 }
+
+    fn contains(&self, pt: Vec2) -> bool {
+        let bounds = self.bounds();
+        pt.x >= bounds.pos.x
+            && pt.y >= bounds.pos.y
+            && pt.x <= bounds.pos.x + bounds.siz.x
+            && pt.y <= bounds.pos.y + bounds.siz.y
+    }
+
+    fn has_line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        let direction = (to - from).normalize_or_zero();
+        let distance = from.distance(to);
+        if distance == 0.0 {
+            return true;
+        }
+
+        let ray = Ray {
+            root: from,
+            dir: direction,
+        };
+
+        self.raycast(ray).is_none_or(|hit| hit.dist >= distance)
+    }
+}
+
+/// Forwards every query to another world and counts the collision queries, used for search statistics.
+pub struct CountingGeometry<'a> {
+    inner: &'a dyn Geometry,
+    raycasts: AtomicUsize,
+    segment_checks: AtomicUsize,
+}
+
+impl<'a> CountingGeometry<'a> {
+    pub fn new(inner: &'a dyn Geometry) -> Self {
+        Self {
+            inner,
+            raycasts: AtomicUsize::new(0),
+            segment_checks: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn raycasts(&self) -> usize { self.raycasts.load(Ordering::Relaxed) }
+    pub fn segment_checks(&self) -> usize { self.segment_checks.load(Ordering::Relaxed) }
+}
+
+impl Geometry for CountingGeometry<'_> {
+    fn bounds(&self) -> Quad { self.inner.bounds() }
+
+    fn raycast(&self, ray: Ray) -> Option<RayHitInfo> {
+        self.raycasts.fetch_add(1, Ordering::Relaxed);
+        self.inner.raycast(ray)
+    }
+
+    fn is_point_in_obstacle(&self, pt: Vec2) -> bool { self.inner.is_point_in_obstacle(pt) }
+
+    fn is_segment_clear(&self, from: Vec2, to: Vec2, radius: f32) -> bool {
+        self.segment_checks.fetch_add(1, Ordering::Relaxed);
+        self.inner.is_segment_clear(from, to, radius)
+    }
+
+    fn zones(&self) -> Option<&ZoneLayer> { self.inner.zones() }
+
+    fn segment_hazard(&self, from: Vec2, to: Vec2) -> f64 { self.inner.segment_hazard(from, to) }
+}
+
+/// Parameter range `[t0, t1]` of the segment `from -> to` lying inside the box, `None` if it misses.
+/// Touching the boundary only counts as a hit when `closed` is set.
+pub fn clip_segment_to_box(from: Vec2, to: Vec2, box_min: Vec2, box_max: Vec2, closed: bool) -> Option<(f32, f32)> {
+    let delta = to - from;
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+
+    let checks = [
+        (-delta.x, from.x - box_min.x),
+        (delta.x, box_max.x - from.x),
+        (-delta.y, from.y - box_min.y),
+        (delta.y, box_max.y - from.y),
+    ];
+
+    for (p, q) in checks {
+        if p == 0.0 {
+            if q < 0.0 || (!closed && q == 0.0) {
+                return None;
+            }
+            continue;
+        }
+
+        let r = q / p;
+        if p < 0.0 {
+            t0 = t0.max(r);
+        } else {
+            t1 = t1.min(r);
+        }
+    }
+
+    if t0 < t1 || (closed && t0 == t1) {
+        Some((t0, t1))
+    } else {
+        None
+    }
+}
+
+pub fn point_segment_distance(pt: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return pt.distance(a);
+    }
+
+    let t = ((pt - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    pt.distance(a + ab * t)
+}
+
+/// Intersection parameters `(t, u)` of segments `a0 -> a1` and `b0 -> b1`, both in `[0, 1]`.
+pub fn segment_intersection(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Option<(f32, f32)> {
+    let r = a1 - a0;
+    let s = b1 - b0;
+    let denom = r.perp_dot(s);
+    if denom == 0.0 {
+        return None;
+    }
+
+    let diff = b0 - a0;
+    let t = diff.perp_dot(s) / denom;
+    let u = diff.perp_dot(r) / denom;
+
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some((t, u))
+    } else {
+        None
+    }
+}
+
+pub fn segment_segment_distance(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> f32 {
+    if segment_intersection(a0, a1, b0, b1).is_some() {
+        return 0.0;
+    }
+
+    point_segment_distance(a0, b0, b1)
+        .min(point_segment_distance(a1, b0, b1))
+        .min(point_segment_distance(b0, a0, a1))
+        .min(point_segment_distance(b1, a0, a1))
+}
+
+pub fn segment_box_distance(from: Vec2, to: Vec2, box_min: Vec2, box_max: Vec2) -> f32 {
+    if clip_segment_to_box(from, to, box_min, box_max, true).is_some() {
+        return 0.0;
+    }
+
+    let corners = [
+        box_min,
+        Vec2::new(box_max.x, box_min.y),
+        box_max,
+        Vec2::new(box_min.x, box_max.y),
+    ];
+
+    let mut dist = f32::MAX;
+    for i in 0..corners.len() {
+        let edge_from = corners[i];
+        let edge_to = corners[(i + 1) % corners.len()];
+        dist = dist.min(segment_segment_distance(from, to, edge_from, edge_to));
+    }
+    dist
+}
@@ -1,6 +1,10 @@
+use crate::world::geometry::{clip_segment_to_box, segment_box_distance, Geometry};
+use crate::world::occupancy::{OccupancyConfig, OccupancyGrid};
+use crate::world::quadtree::QuadTree;
 use crate::world::types::{quad, Quad, Ray, RayHitInfo};
+use crate::world::zone::ZoneLayer;
 use crate::world::WorldConfig;
-use macroquad::prelude::*;
+use glam::Vec2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GridNodeValue {
@@ -13,19 +17,23 @@ pub struct Grid {
     width: usize,
     height: usize,
     data: Vec<GridNodeValue>,
+    quadtree: Option<QuadTree>,
 }
 
 pub struct GridMap {
     grid: Box<Grid>,
     config: WorldConfig,
+    zones: ZoneLayer,
+    occupancy: Option<OccupancyGrid>,
 }
 
 impl Grid {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
-            width: width,
-            height: height,
+            width,
+            height,
             data: vec![GridNodeValue::Air; width * height],
+            quadtree: None,
         }
     }
 
@@ -40,6 +48,9 @@ impl Grid {
     pub fn set(&mut self, x: usize, y: usize, val: GridNodeValue) -> Option<()> {
         let pos = self.get_grid_pos(x, y)?;
         self.data[pos] = val;
+        if let Some(quadtree) = self.quadtree.as_mut() {
+            quadtree.update(x, y, val);
+        }
         Some(())
     }
 
@@ -53,6 +64,15 @@ impl Grid {
         Some(self.data[pos] == GridNodeValue::Air)
     }
 
+    /// Builds a quadtree over the current cells, later `set` calls keep it up to date.
+    pub fn build_quadtree(&mut self) -> &QuadTree {
+        let quadtree = QuadTree::new(self);
+        self.quadtree.insert(quadtree)
+    }
+
+    pub fn quadtree(&self) -> Option<&QuadTree> { self.quadtree.as_ref() }
+    pub fn drop_quadtree(&mut self) { self.quadtree = None; }
+
     fn get_grid_pos(&self, x: usize, y: usize) -> Option<usize> {
         if x >= self.width || y >= self.height {
             return None;
@@ -67,13 +87,33 @@ impl GridMap {
         let grid = Grid::new(config.grid_size.0, config.grid_size.1);
         Self {
             grid: Box::new(grid),
-            config: config,
+            config,
+            zones: ZoneLayer::new(),
+            occupancy: None,
         }
     }
 
     pub fn grid(&self) -> &Grid { self.grid.as_ref() }
     pub fn grid_mut(&mut self) -> &mut Grid { self.grid.as_mut() }
 
+    pub fn zones(&self) -> &ZoneLayer { &self.zones }
+    pub fn zones_mut(&mut self) -> &mut ZoneLayer { &mut self.zones }
+
+    pub fn occupancy(&self) -> Option<&OccupancyGrid> { self.occupancy.as_ref() }
+    pub fn occupancy_mut(&mut self) -> Option<&mut OccupancyGrid> { self.occupancy.as_mut() }
+
+    /// Attaches an occupancy layer covering the grid with every cell at the configured prior.
+    pub fn enable_occupancy(&mut self, config: OccupancyConfig) -> &mut OccupancyGrid {
+        self.occupancy.insert(OccupancyGrid::new(
+            self.grid.width,
+            self.grid.height,
+            self.config.cell_size,
+            config,
+        ))
+    }
+
+    pub fn disable_occupancy(&mut self) { self.occupancy = None; }
+
     pub fn width(&self) -> usize { self.grid.width }
     pub fn height(&self) -> usize { self.grid.height }
     pub fn cell_size(&self) -> f32 { self.config.cell_size }
@@ -91,6 +131,20 @@ impl GridMap {
         ))
     }
 
+    pub fn world_to_cell(&self, pos: Vec2) -> Option<(usize, usize)> {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+
+        let x = (pos.x / self.config.cell_size).floor() as usize;
+        let y = (pos.y / self.config.cell_size).floor() as usize;
+        if x >= self.grid.width || y >= self.grid.height {
+            return None;
+        }
+
+        Some((x, y))
+    }
+
     pub fn raycast(&self, ray: Ray) -> Option<RayHitInfo> {
         let dir_norm = ray.dir.normalize_or_zero();
         if dir_norm == Vec2::new(0.0, 0.0) {
@@ -232,3 +286,135 @@ impl GridMap {
         }
     }
 }
+
+impl Geometry for GridMap {
+    fn bounds(&self) -> Quad {
+        quad(
+            0.0,
+            0.0,
+            self.grid.width as f32 * self.config.cell_size,
+            self.grid.height as f32 * self.config.cell_size,
+        )
+    }
+
+    fn raycast(&self, ray: Ray) -> Option<RayHitInfo> { GridMap::raycast(self, ray) }
+
+    fn zones(&self) -> Option<&ZoneLayer> { Some(&self.zones) }
+
+    fn segment_hazard(&self, from: Vec2, to: Vec2) -> f64 {
+        self.occupancy.as_ref().map_or(0.0, |occupancy| occupancy.segment_hazard(from, to))
+    }
+
+    fn is_point_in_obstacle(&self, pt: Vec2) -> bool {
+        self.world_to_cell(pt)
+            .is_some_and(|(x, y)| self.grid.get(x, y) == Some(GridNodeValue::Obstacle))
+    }
+
+    fn is_segment_clear(&self, from: Vec2, to: Vec2, radius: f32) -> bool {
+        if self.grid.width == 0 || self.grid.height == 0 {
+            return true;
+        }
+
+        let cell_size = self.config.cell_size;
+        let radius = radius.max(0.0);
+
+        // Everything outside the map is solid, the segment and bounds are convex so checking the ends suffices
+        let bounds = self.bounds();
+        let inner_min = bounds.pos + Vec2::splat(radius);
+        let inner_max = bounds.pos + bounds.siz - Vec2::splat(radius);
+        for pt in [from, to] {
+            if !(pt.cmpge(inner_min).all() && pt.cmple(inner_max).all()) {
+                return false;
+            }
+        }
+
+        let max_x = self.grid.width as isize - 1;
+        let max_y = self.grid.height as isize - 1;
+
+        let min_row = (((from.y.min(to.y) - radius) / cell_size).floor() as isize).clamp(0, max_y);
+        let max_row = (((from.y.max(to.y) + radius) / cell_size).floor() as isize).clamp(0, max_y);
+
+        // Walk the rows touched by the swept disc and only test the columns the segment covers in each row.
+        let span = bounds.siz.x + bounds.siz.y + radius;
+        for y in min_row..=max_row {
+            let slab_min = Vec2::new(-span, y as f32 * cell_size - radius);
+            let slab_max = Vec2::new(span, (y + 1) as f32 * cell_size + radius);
+            let Some((t0, t1)) = clip_segment_to_box(from, to, slab_min, slab_max, true) else {
+                continue;
+            };
+
+            let x0 = from.x + (to.x - from.x) * t0;
+            let x1 = from.x + (to.x - from.x) * t1;
+            let min_col = (((x0.min(x1) - radius) / cell_size).floor() as isize).clamp(0, max_x);
+            let max_col = (((x0.max(x1) + radius) / cell_size).floor() as isize).clamp(0, max_x);
+
+            for x in min_col..=max_col {
+                if self.grid.get(x as usize, y as usize) != Some(GridNodeValue::Obstacle) {
+                    continue;
+                }
+
+                let cell_min = Vec2::new(x as f32 * cell_size, y as f32 * cell_size);
+                let cell_max = cell_min + Vec2::splat(cell_size);
+                let blocked = if radius > 0.0 {
+                    segment_box_distance(from, to, cell_min, cell_max) < radius
+                } else {
+                    clip_segment_to_box(from, to, cell_min, cell_max, false).is_some()
+                        || self.squeezes_past_corner(from, to, x, y, cell_min, cell_max)
+                        || self.slides_between_cells(from, to, x, y, cell_min, cell_max)
+                };
+                if blocked {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl GridMap {
+    /// `true` if the segment passes through a corner of obstacle cell `(x, y)` that it shares with a diagonally
+    /// touching obstacle cell, slipping through the zero-width gap between the two.
+    fn squeezes_past_corner(&self, from: Vec2, to: Vec2, x: isize, y: isize, cell_min: Vec2, cell_max: Vec2) -> bool {
+        // Touching a box in a single point strictly inside the segment only happens at one of its corners
+        let Some((t0, t1)) = clip_segment_to_box(from, to, cell_min, cell_max, true) else {
+            return false;
+        };
+        if t0 != t1 || t0 <= 0.0 || t0 >= 1.0 {
+            return false;
+        }
+
+        let center = (cell_min + cell_max) * 0.5;
+        let touch = from.lerp(to, t0);
+        let dx = if touch.x > center.x { 1 } else { -1 };
+        let dy = if touch.y > center.y { 1 } else { -1 };
+        let (Ok(diagonal_x), Ok(diagonal_y)) = (usize::try_from(x + dx), usize::try_from(y + dy)) else {
+            return false;
+        };
+        self.grid.get(diagonal_x, diagonal_y) == Some(GridNodeValue::Obstacle)
+    }
+
+    /// `true` if the segment runs along an edge of obstacle cell `(x, y)` that it shares with another obstacle cell,
+    /// passing through the seam between the two.
+    fn slides_between_cells(&self, from: Vec2, to: Vec2, x: isize, y: isize, cell_min: Vec2, cell_max: Vec2) -> bool {
+        // Overlapping the closed box over a stretch without entering its interior only happens along an edge
+        let Some((t0, t1)) = clip_segment_to_box(from, to, cell_min, cell_max, true) else {
+            return false;
+        };
+        if t0 == t1 {
+            return false;
+        }
+
+        let (dx, dy) = if from.y == to.y {
+            (0, if from.y == cell_min.y { -1 } else { 1 })
+        } else if from.x == to.x {
+            (if from.x == cell_min.x { -1 } else { 1 }, 0)
+        } else {
+            return false;
+        };
+        let (Ok(side_x), Ok(side_y)) = (usize::try_from(x + dx), usize::try_from(y + dy)) else {
+            return false;
+        };
+        self.grid.get(side_x, side_y) == Some(GridNodeValue::Obstacle)
+    }
+}
@@ -0,0 +1,395 @@
+use crate::world::clearance::ClearanceField;
+use crate::world::grid::GridMap;
+use crate::world::roadmap::Roadmap;
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MedialAxisConfig {
+    /// Skeleton cells closer than this to an obstacle are dropped, use the agent radius.
+    pub min_clearance: f32,
+    /// How strongly edge costs grow as clearance shrinks, zero plans by length along the skeleton.
+    pub clearance_weight: f32,
+}
+
+impl Default for MedialAxisConfig {
+    fn default() -> Self {
+        Self {
+            min_clearance: 0.0,
+            clearance_weight: 1.0,
+        }
+    }
+}
+
+/// Roadmap on the medial axis of free space, routes along it keep as far from obstacles as the map allows.
+#[derive(Debug, Clone)]
+pub struct MedialAxisRoadmap {
+    roadmap: Roadmap,
+    clearance: ClearanceField,
+    /// Roadmap node of every skeleton cell, `usize::MAX` elsewhere.
+    cell_node: Vec<usize>,
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    config: MedialAxisConfig,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SearchNode {
+    cell: usize,
+    cost: f32,
+}
+
+impl Ord for SearchNode {
+    fn cmp(&self, other: &Self) -> Ordering { other.cost.total_cmp(&self.cost) }
+}
+impl PartialOrd for SearchNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl PartialEq for SearchNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for SearchNode {}
+
+const NEIGHBOURS: [(isize, isize); 8] = [(1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1), (0, 1), (1, 1)];
+
+impl MedialAxisRoadmap {
+    pub fn new(grid_map: &GridMap, config: MedialAxisConfig) -> Self {
+        let width = grid_map.width();
+        let height = grid_map.height();
+        let clearance = ClearanceField::new(grid_map);
+        let skeleton = skeletonize(&clearance);
+
+        let mut medial_axis = Self {
+            roadmap: Roadmap::new(),
+            clearance,
+            cell_node: vec![usize::MAX; width * height],
+            width,
+            height,
+            cell_size: grid_map.cell_size(),
+            config,
+        };
+
+        for (index, _) in skeleton.iter().enumerate().filter(|(_, on_skeleton)| **on_skeleton) {
+            let (x, y) = (index % width, index / width);
+            if medial_axis.clearance.cell_clearance(x, y).unwrap_or(0.0) < config.min_clearance {
+                continue;
+            }
+            medial_axis.cell_node[index] = medial_axis.roadmap.add_node(medial_axis.cell_center(index));
+        }
+
+        for index in 0..width * height {
+            let node = medial_axis.cell_node[index];
+            if node == usize::MAX {
+                continue;
+            }
+
+            // Half of the neighbourhood is enough, the other half links back to this cell
+            let links: Vec<(usize, f32)> = medial_axis
+                .moves(index)
+                .filter(|&next| next > index && medial_axis.cell_node[next] != usize::MAX)
+                .map(|next| (medial_axis.cell_node[next], medial_axis.move_cost(index, next)))
+                .collect();
+            for (next_node, cost) in links {
+                medial_axis.roadmap.add_edge(node, next_node, cost);
+            }
+        }
+        medial_axis
+    }
+
+    pub fn roadmap(&self) -> &Roadmap { &self.roadmap }
+    pub fn clearance(&self) -> &ClearanceField { &self.clearance }
+    pub fn config(&self) -> &MedialAxisConfig { &self.config }
+
+    /// Max-clearance route between two free points, as `(path, nodes expanded)`. Both are first retracted onto the
+    /// skeleton along the cheapest clearance weighted path, then the roadmap carries the route between the two
+    /// retractions.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<(Vec<Vec2>, usize)> {
+        let start_connector = self.connector(start)?;
+        let goal_connector = self.connector(goal)?;
+        let start_node = self.cell_node[start_connector.cells[start_connector.cells.len() - 1]];
+        let goal_node = self.cell_node[goal_connector.cells[goal_connector.cells.len() - 1]];
+
+        let (on_roadmap, expanded) = self.roadmap.shortest_path(
+            start,
+            goal,
+            &[(start_node, start_connector.cost)],
+            &[(goal_node, goal_connector.cost)],
+            None,
+        )?;
+
+        let mut path = vec![start];
+        path.extend(start_connector.cells.iter().map(|&cell| self.cell_center(cell)));
+        // The first and last roadmap nodes already end the connectors
+        let nodes = &on_roadmap[1..on_roadmap.len() - 1];
+        path.extend(nodes.iter().skip(1).take(nodes.len().saturating_sub(2)).copied());
+        path.extend(goal_connector.cells.iter().rev().map(|&cell| self.cell_center(cell)));
+        path.push(goal);
+        let expanded = expanded + start_connector.expanded + goal_connector.expanded;
+        Some((simplify_collinear(path), expanded))
+    }
+
+    /// Cheapest walk from the cell containing `pt` to the closest skeleton cell.
+    fn connector(&self, pt: Vec2) -> Option<Connector> {
+        if pt.x < 0.0 || pt.y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = ((pt.x / self.cell_size) as usize, (pt.y / self.cell_size) as usize);
+        if x >= self.width || y >= self.height || self.clearance.cell_distance(x, y)? == 0.0 {
+            return None;
+        }
+
+        let start = y * self.width + x;
+        let mut costs = vec![f32::INFINITY; self.width * self.height];
+        let mut predecessors = vec![usize::MAX; self.width * self.height];
+        let mut queue = BinaryHeap::new();
+        costs[start] = 0.0;
+        queue.push(SearchNode { cell: start, cost: 0.0 });
+
+        let mut expanded = 0;
+        while let Some(cur) = queue.pop() {
+            if cur.cost > costs[cur.cell] {
+                continue;
+            }
+            expanded += 1;
+
+            if self.cell_node[cur.cell] != usize::MAX {
+                let mut cell = cur.cell;
+                let mut cells = vec![cell];
+                while predecessors[cell] != usize::MAX {
+                    cell = predecessors[cell];
+                    cells.push(cell);
+                }
+                cells.reverse();
+                return Some(Connector {
+                    cells,
+                    cost: cur.cost,
+                    expanded,
+                });
+            }
+
+            for next in self.moves(cur.cell) {
+                let cost = cur.cost + self.move_cost(cur.cell, next);
+                if cost < costs[next] {
+                    costs[next] = cost;
+                    predecessors[next] = cur.cell;
+                    queue.push(SearchNode { cell: next, cost });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Free 8-neighbours of a cell, diagonal moves need both side cells free so they never pinch a corner.
+    fn moves(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = (index % self.width, index / self.width);
+        let free = move |dx: isize, dy: isize| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (self.clearance.cell_distance(nx, ny)? > 0.0).then_some(ny * self.width + nx)
+        };
+
+        NEIGHBOURS.into_iter().filter_map(move |(dx, dy)| {
+            let next = free(dx, dy)?;
+            if dx != 0 && dy != 0 && (free(dx, 0).is_none() || free(0, dy).is_none()) {
+                return None;
+            }
+            Some(next)
+        })
+    }
+
+    /// Length scaled up by how close the move runs to obstacles, never below the plain length.
+    fn move_cost(&self, from: usize, to: usize) -> f32 {
+        let clearance_of = |index: usize| {
+            self.clearance
+                .cell_clearance(index % self.width, index / self.width)
+                .unwrap_or(0.0)
+                .max(f32::EPSILON)
+        };
+        let clearance = clearance_of(from).min(clearance_of(to));
+        let length = self.cell_center(from).distance(self.cell_center(to));
+        length * (1.0 + self.config.clearance_weight * self.cell_size / clearance)
+    }
+
+    fn cell_center(&self, index: usize) -> Vec2 {
+        Vec2::new(
+            ((index % self.width) as f32 + 0.5) * self.cell_size,
+            ((index / self.width) as f32 + 0.5) * self.cell_size,
+        )
+    }
+}
+
+impl GridMap {
+    pub fn build_medial_axis(&self, config: MedialAxisConfig) -> MedialAxisRoadmap { MedialAxisRoadmap::new(self, config) }
+}
+
+struct Connector {
+    /// Cells from the query cell to the skeleton cell, both included.
+    cells: Vec<usize>,
+    cost: f32,
+    /// Cells taken off the queue while looking for the skeleton.
+    expanded: usize,
+}
+
+/// Distance ordered homotopic thinning. Ridge cells of the clearance field are kept as anchors and every
+/// other free cell is peeled off, closest to an obstacle first, as long as removing it keeps the topology.
+fn skeletonize(clearance: &ClearanceField) -> Vec<bool> {
+    let width = clearance.width();
+    let height = clearance.height();
+    let dist_at = |x: isize, y: isize| {
+        if x < 0 || y < 0 {
+            return 0.0;
+        }
+        clearance.cell_distance(x as usize, y as usize).unwrap_or(0.0)
+    };
+
+    let mut skeleton: Vec<bool> = (0..width * height)
+        .map(|i| dist_at((i % width) as isize, (i / width) as isize) > 0.0)
+        .collect();
+
+    let mut order: Vec<usize> = (0..width * height)
+        .filter(|&i| {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+            let dist = dist_at(x, y);
+            skeleton[i]
+                && !is_ridge(dist, dist_at(x - 1, y), dist_at(x + 1, y))
+                && !is_ridge(dist, dist_at(x, y - 1), dist_at(x, y + 1))
+        })
+        .collect();
+    order.sort_by(|&a, &b| {
+        dist_at((a % width) as isize, (a / width) as isize)
+            .total_cmp(&dist_at((b % width) as isize, (b / width) as isize))
+    });
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &index in order.iter() {
+            if skeleton[index] && is_simple(&skeleton, width, height, index) {
+                skeleton[index] = false;
+                changed = true;
+            }
+        }
+    }
+    skeleton
+}
+
+/// Local maximum across a line of three cells, plateaus only count where they drop on one side.
+fn is_ridge(dist: f32, before: f32, after: f32) -> bool { dist >= before && dist >= after && (dist > before || dist > after) }
+
+/// A cell is simple when removing it neither splits the 8-connected foreground nor joins or opens
+/// 4-connected holes around it.
+fn is_simple(skeleton: &[bool], width: usize, height: usize, index: usize) -> bool {
+    let (x, y) = (index % width, index / width);
+    let ring: Vec<(isize, isize, bool)> = NEIGHBOURS
+        .iter()
+        .map(|&(dx, dy)| {
+            let inside = x.checked_add_signed(dx).zip(y.checked_add_signed(dy));
+            let set = inside.is_some_and(|(nx, ny)| nx < width && ny < height && skeleton[ny * width + nx]);
+            (dx, dy, set)
+        })
+        .collect();
+
+    let components = |foreground: bool, eight_connected: bool, needs_edge_neighbour: bool| {
+        let mut seen = [false; 8];
+        let mut count = 0;
+        for start in 0..ring.len() {
+            if seen[start] || ring[start].2 != foreground {
+                continue;
+            }
+
+            seen[start] = true;
+            let mut stack = vec![start];
+            let mut touches_center = false;
+            while let Some(cur) = stack.pop() {
+                let (cx, cy, _) = ring[cur];
+                touches_center |= cx == 0 || cy == 0;
+                for next in 0..ring.len() {
+                    let (nx, ny, set) = ring[next];
+                    let (ax, ay) = ((cx - nx).abs(), (cy - ny).abs());
+                    let adjacent = if eight_connected { ax.max(ay) == 1 } else { ax + ay == 1 };
+                    if !seen[next] && set == foreground && adjacent {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+
+            if !needs_edge_neighbour || touches_center {
+                count += 1;
+            }
+        }
+        count
+    };
+
+    components(true, true, false) == 1 && components(false, false, true) == 1
+}
+
+fn simplify_collinear(path: Vec<Vec2>) -> Vec<Vec2> {
+    let mut simplified: Vec<Vec2> = Vec::with_capacity(path.len());
+    for pt in path {
+        if let [.., a, b] = simplified.as_slice()
+            && (*b - *a).perp_dot(pt - *b).abs() <= f32::EPSILON * (*b - *a).length() * (pt - *b).length()
+            && (*b - *a).dot(pt - *b) >= 0.0
+        {
+            simplified.pop();
+        }
+        if simplified.last() != Some(&pt) {
+            simplified.push(pt);
+        }
+    }
+    simplified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+    use crate::world::grid::GridNodeValue;
+
+    /// A 150 x 70 world walled top and bottom, leaving a corridor five cells high centered on row 3.
+    fn corridor_map() -> GridMap {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (15, 7),
+            cell_size: 10.0,
+        });
+        for x in 0..15 {
+            grid_map.grid_mut().set(x, 0, GridNodeValue::Obstacle);
+            grid_map.grid_mut().set(x, 6, GridNodeValue::Obstacle);
+        }
+        grid_map
+    }
+
+    #[test]
+    fn skeleton_follows_the_corridor_center_line() {
+        let grid_map = corridor_map();
+        let skeleton = skeletonize(&ClearanceField::new(&grid_map));
+
+        // Away from the corridor's ends the skeleton is exactly the center row
+        for x in 3..12 {
+            let column: Vec<usize> = (0..7).filter(|&y| skeleton[y * 15 + x]).collect();
+            assert_eq!(column, vec![3], "column {}", x);
+        }
+    }
+
+    #[test]
+    fn route_runs_along_the_center_line() {
+        let roadmap = MedialAxisRoadmap::new(&corridor_map(), MedialAxisConfig::default());
+        let (path, expanded) = roadmap.find_path(Vec2::new(35.0, 15.0), Vec2::new(115.0, 55.0)).unwrap();
+        assert!(expanded > 0);
+
+        // Straight onto the center line, along it and off again to the goal
+        assert_eq!(
+            path,
+            vec![
+                Vec2::new(35.0, 15.0),
+                Vec2::new(35.0, 35.0),
+                Vec2::new(115.0, 35.0),
+                Vec2::new(115.0, 55.0),
+            ]
+        );
+    }
+}
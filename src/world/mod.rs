@@ -1,5 +1,16 @@
+pub mod analysis;
+pub mod clearance;
+pub mod geometry;
 pub mod grid;
+pub mod medial_axis;
+pub mod navmesh;
+pub mod occupancy;
+pub mod polygon;
+pub mod quadtree;
+pub mod roadmap;
 pub mod types;
+pub mod visibility;
+pub mod zone;
 
 #[derive(Debug, Clone)]
 pub struct WorldConfig {
@@ -0,0 +1,435 @@
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::polygon::Polygon;
+use crate::world::types::quad;
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Edge shared by two neighbouring navmesh polygons, the agent can cross anywhere along `a -> b`.
+#[derive(Debug, Clone, Copy)]
+pub struct NavPortal {
+    pub to: usize,
+    pub a: Vec2,
+    pub b: Vec2,
+}
+
+impl NavPortal {
+    pub fn midpoint(&self) -> Vec2 { (self.a + self.b) * 0.5 }
+}
+
+/// Convex polygons covering the free space an agent of `agent_radius` can stand in.
+#[derive(Debug, Clone)]
+pub struct NavMesh {
+    polygons: Vec<Polygon>,
+    portals: Vec<Vec<NavPortal>>,
+    /// Polygon covering each cell, `usize::MAX` for cells the agent cannot enter.
+    cell_poly: Vec<usize>,
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    agent_radius: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SearchNode {
+    state: usize,
+    f: f32,
+}
+
+impl Ord for SearchNode {
+    fn cmp(&self, other: &Self) -> Ordering { other.f.total_cmp(&self.f) }
+}
+impl PartialOrd for SearchNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl PartialEq for SearchNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for SearchNode {}
+
+impl NavMesh {
+    /// Erodes the free cells by `agent_radius` and merges what is left into maximal rectangles.
+    pub fn new(grid_map: &GridMap, agent_radius: f32) -> Self {
+        let width = grid_map.width();
+        let height = grid_map.height();
+        let cell_size = grid_map.cell_size();
+        let walkable = walkable_cells(grid_map, agent_radius);
+
+        let mut cell_poly = vec![usize::MAX; width * height];
+        let mut rects: Vec<(usize, usize, usize, usize)> = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if !walkable[y * width + x] || cell_poly[y * width + x] != usize::MAX {
+                    continue;
+                }
+
+                let free = |cx: usize, cy: usize| walkable[cy * width + cx] && cell_poly[cy * width + cx] == usize::MAX;
+                let mut rect_width = 1;
+                while x + rect_width < width && free(x + rect_width, y) {
+                    rect_width += 1;
+                }
+                let mut rect_height = 1;
+                while y + rect_height < height && (x..x + rect_width).all(|cx| free(cx, y + rect_height)) {
+                    rect_height += 1;
+                }
+
+                let id = rects.len();
+                for cy in y..y + rect_height {
+                    for cx in x..x + rect_width {
+                        cell_poly[cy * width + cx] = id;
+                    }
+                }
+                rects.push((x, y, rect_width, rect_height));
+            }
+        }
+
+        let polygons = rects
+            .iter()
+            .map(|&(x, y, w, h)| {
+                Polygon::rect(quad(
+                    x as f32 * cell_size,
+                    y as f32 * cell_size,
+                    w as f32 * cell_size,
+                    h as f32 * cell_size,
+                ))
+            })
+            .collect();
+
+        let mut navmesh = Self {
+            polygons,
+            portals: vec![Vec::new(); rects.len()],
+            cell_poly,
+            width,
+            height,
+            cell_size,
+            agent_radius,
+        };
+
+        for (id, &(x, y, w, h)) in rects.iter().enumerate() {
+            // Only look right and down, the opposite direction is added at the same time
+            if x + w < width {
+                navmesh.link_side(id, x + w, y..y + h, true);
+            }
+            if y + h < height {
+                navmesh.link_side(id, y + h, x..x + w, false);
+            }
+        }
+        navmesh
+    }
+
+    /// Adds a portal for every run of cells across the grid line `line` that belongs to the same polygon.
+    /// `vertical` lines run along y at x = `line`.
+    fn link_side(&mut self, id: usize, line: usize, range: std::ops::Range<usize>, vertical: bool) {
+        let corner = |along: usize| if vertical { (line, along) } else { (along, line) };
+        let cell_poly = |along: usize| {
+            let (x, y) = corner(along);
+            self.cell_poly[y * self.width + x]
+        };
+
+        let mut along = range.start;
+        let mut links = Vec::new();
+        while along < range.end {
+            let other = cell_poly(along);
+            let run_start = along;
+            while along < range.end && cell_poly(along) == other {
+                along += 1;
+            }
+
+            if other != usize::MAX {
+                let (a, b) = (corner(run_start), corner(along));
+                let cs = self.cell_size;
+                links.push(NavPortal {
+                    to: other,
+                    a: Vec2::new(a.0 as f32 * cs, a.1 as f32 * cs),
+                    b: Vec2::new(b.0 as f32 * cs, b.1 as f32 * cs),
+                });
+            }
+        }
+
+        for portal in links {
+            self.portals[portal.to].push(NavPortal { to: id, ..portal });
+            self.portals[id].push(portal);
+        }
+    }
+
+    pub fn polygons(&self) -> &[Polygon] { &self.polygons }
+    pub fn portals(&self, poly: usize) -> &[NavPortal] { self.portals.get(poly).map_or(&[], |portals| portals) }
+    pub fn agent_radius(&self) -> f32 { self.agent_radius }
+
+    /// Index of the polygon containing `pt`, `None` if the agent cannot stand there.
+    pub fn locate(&self, pt: Vec2) -> Option<usize> {
+        if pt.x < 0.0 || pt.y < 0.0 {
+            return None;
+        }
+
+        let x = (pt.x / self.cell_size).floor() as usize;
+        let y = (pt.y / self.cell_size).floor() as usize;
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let poly = self.cell_poly[y * self.width + x];
+        (poly != usize::MAX).then_some(poly)
+    }
+
+    /// Polygons to travel through from `start` to `goal`, as `(corridor, nodes expanded)`. A* runs over the directed
+    /// portals with every crossing placed at the portal midpoint, which keeps long polygons from hiding detours.
+    pub fn find_corridor(&self, start: Vec2, goal: Vec2) -> Option<(Vec<usize>, usize)> {
+        let start_poly = self.locate(start)?;
+        let goal_poly = self.locate(goal)?;
+
+        // State `i` is the `i`-th directed portal, the extra last state is standing at `start`
+        let mut offsets = Vec::with_capacity(self.portals.len());
+        let mut states: Vec<(usize, NavPortal)> = Vec::new();
+        for (poly, portals) in self.portals.iter().enumerate() {
+            offsets.push(states.len());
+            states.extend(portals.iter().map(|portal| (poly, *portal)));
+        }
+        let start_state = states.len();
+        let position = |state: usize| {
+            if state == start_state {
+                start
+            } else {
+                states[state].1.midpoint()
+            }
+        };
+        let entered = |state: usize| if state == start_state { start_poly } else { states[state].1.to };
+
+        let mut g_costs = vec![f32::INFINITY; states.len() + 1];
+        let mut predecessors = vec![usize::MAX; states.len() + 1];
+        let mut queue = BinaryHeap::new();
+
+        g_costs[start_state] = 0.0;
+        queue.push(SearchNode {
+            state: start_state,
+            f: start.distance(goal),
+        });
+
+        let mut best_goal: Option<(f32, usize)> = None;
+        let mut expanded = 0;
+        while let Some(cur) = queue.pop() {
+            if best_goal.is_some_and(|(cost, _)| cur.f >= cost) {
+                break;
+            }
+
+            let cur_g = g_costs[cur.state];
+            let cur_pos = position(cur.state);
+            if cur.f > cur_g + cur_pos.distance(goal) {
+                continue;
+            }
+            expanded += 1;
+
+            let poly = entered(cur.state);
+            if poly == goal_poly {
+                let cost = cur_g + cur_pos.distance(goal);
+                if best_goal.is_none_or(|(best, _)| cost < best) {
+                    best_goal = Some((cost, cur.state));
+                }
+                continue;
+            }
+
+            for (i, portal) in self.portals[poly].iter().enumerate() {
+                let next = offsets[poly] + i;
+                let point = portal.midpoint();
+                let new_g = cur_g + cur_pos.distance(point);
+                if new_g < g_costs[next] {
+                    g_costs[next] = new_g;
+                    predecessors[next] = cur.state;
+                    queue.push(SearchNode {
+                        state: next,
+                        f: new_g + point.distance(goal),
+                    });
+                }
+            }
+        }
+
+        let (_, mut state) = best_goal?;
+        let mut corridor = vec![goal_poly];
+        while state != start_state {
+            corridor.push(states[state].0);
+            state = predecessors[state];
+        }
+        corridor.reverse();
+        Some((corridor, expanded))
+    }
+
+    /// Shortest path through the corridor using the simple stupid funnel algorithm.
+    pub fn string_pull(&self, corridor: &[usize], start: Vec2, goal: Vec2) -> Vec<Vec2> {
+        let mut portals = vec![(start, start)];
+        for pair in corridor.windows(2) {
+            let Some(portal) = self.portals[pair[0]].iter().find(|portal| portal.to == pair[1]) else {
+                continue;
+            };
+
+            // Orient the portal as (left, right) seen from the polygon it is entered from
+            let heading = center(&self.polygons[pair[1]]) - center(&self.polygons[pair[0]]);
+            if heading.perp_dot(portal.a - portal.midpoint()) > 0.0 {
+                portals.push((portal.a, portal.b));
+            } else {
+                portals.push((portal.b, portal.a));
+            }
+        }
+        portals.push((goal, goal));
+
+        let mut path = vec![start];
+        let (mut apex, mut left, mut right) = (start, start, start);
+        let (mut left_index, mut right_index) = (0, 0);
+
+        let mut i = 1;
+        while i < portals.len() {
+            let (new_left, new_right) = portals[i];
+
+            if triangle_area2(apex, right, new_right) >= 0.0 {
+                if apex == right || triangle_area2(apex, left, new_right) < 0.0 {
+                    right = new_right;
+                    right_index = i;
+                } else {
+                    // Right side crossed the left one, the left point becomes a corner of the path
+                    path.push(left);
+                    apex = left;
+                    (right, right_index) = (apex, left_index);
+                    i = left_index + 1;
+                    continue;
+                }
+            }
+
+            if triangle_area2(apex, left, new_left) <= 0.0 {
+                if apex == left || triangle_area2(apex, right, new_left) > 0.0 {
+                    left = new_left;
+                    left_index = i;
+                } else {
+                    path.push(right);
+                    apex = right;
+                    (left, left_index) = (apex, right_index);
+                    i = right_index + 1;
+                    continue;
+                }
+            }
+
+            i += 1;
+        }
+
+        if path.last() != Some(&goal) {
+            path.push(goal);
+        }
+        path
+    }
+
+    /// Shortest path through the mesh, as `(path, nodes expanded)`.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<(Vec<Vec2>, usize)> {
+        let (corridor, expanded) = self.find_corridor(start, goal)?;
+        Some((self.string_pull(&corridor, start, goal), expanded))
+    }
+}
+
+impl GridMap {
+    pub fn build_navmesh(&self, agent_radius: f32) -> NavMesh { NavMesh::new(self, agent_radius) }
+}
+
+fn center(polygon: &Polygon) -> Vec2 {
+    let aabb = polygon.aabb();
+    aabb.pos + aabb.siz * 0.5
+}
+
+/// Twice the signed area of the triangle, positive when `c` lies counter-clockwise of `a -> b`.
+fn triangle_area2(a: Vec2, b: Vec2, c: Vec2) -> f32 { (b - a).perp_dot(c - a) }
+
+/// Cells whose whole square stays at least `agent_radius` away from every obstacle and the map edge.
+fn walkable_cells(grid_map: &GridMap, agent_radius: f32) -> Vec<bool> {
+    let width = grid_map.width();
+    let height = grid_map.height();
+    let cell_size = grid_map.cell_size();
+    let grid = grid_map.grid();
+
+    let mut walkable: Vec<bool> = (0..width * height)
+        .map(|i| grid.get(i % width, i / width) == Some(GridNodeValue::Air))
+        .collect();
+    if agent_radius <= 0.0 {
+        return walkable;
+    }
+
+    let reach = (agent_radius / cell_size).ceil() as isize;
+    for y in 0..height {
+        for x in 0..width {
+            let edge_gap = x.min(y).min(width - 1 - x).min(height - 1 - y) as f32 * cell_size;
+            if edge_gap < agent_radius {
+                walkable[y * width + x] = false;
+            }
+
+            if grid.get(x, y) != Some(GridNodeValue::Obstacle) {
+                continue;
+            }
+
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                        continue;
+                    };
+                    if nx >= width || ny >= height {
+                        continue;
+                    }
+
+                    // Gap between the two cell squares
+                    let gap_x = (dx.abs() - 1).max(0) as f32;
+                    let gap_y = (dy.abs() - 1).max(0) as f32;
+                    if (gap_x * gap_x + gap_y * gap_y).sqrt() * cell_size < agent_radius {
+                        walkable[ny * width + nx] = false;
+                    }
+                }
+            }
+        }
+    }
+    walkable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+
+    /// A 100 x 100 world with a wall at x 50..60 running down from the top edge to y = 70.
+    fn wall_map() -> GridMap {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (10, 10),
+            cell_size: 10.0,
+        });
+        for y in 0..7 {
+            grid_map.grid_mut().set(5, y, GridNodeValue::Obstacle);
+        }
+        grid_map
+    }
+
+    #[test]
+    fn funnel_bends_around_the_wall_corners() {
+        let navmesh = NavMesh::new(&wall_map(), 0.0);
+        let (path, expanded) = navmesh.find_path(Vec2::new(25.0, 25.0), Vec2::new(85.0, 25.0)).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                Vec2::new(25.0, 25.0),
+                Vec2::new(50.0, 70.0),
+                Vec2::new(60.0, 70.0),
+                Vec2::new(85.0, 25.0),
+            ]
+        );
+        assert!(expanded > 0);
+    }
+
+    #[test]
+    fn funnel_goes_straight_when_nothing_is_in_the_way() {
+        let navmesh = NavMesh::new(&wall_map(), 0.0);
+        let (path, _) = navmesh.find_path(Vec2::new(15.0, 85.0), Vec2::new(95.0, 75.0)).unwrap();
+        assert_eq!(path, vec![Vec2::new(15.0, 85.0), Vec2::new(95.0, 75.0)]);
+    }
+
+    #[test]
+    fn portals_link_neighbouring_polygons_both_ways() {
+        let navmesh = NavMesh::new(&wall_map(), 0.0);
+        for poly in 0..navmesh.polygons().len() {
+            for portal in navmesh.portals(poly) {
+                assert!(navmesh.portals(portal.to).iter().any(|back| back.to == poly));
+            }
+        }
+        assert_eq!(navmesh.locate(Vec2::new(55.0, 35.0)), None);
+    }
+}
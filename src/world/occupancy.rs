@@ -0,0 +1,236 @@
+use crate::world::geometry::Geometry;
+use crate::world::types::Ray;
+use glam::Vec2;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OccupancyConfig {
+    /// Occupancy probability of cells that were never observed.
+    pub prior: f32,
+    /// Log-odds added to the cell a beam ends in.
+    pub hit_log_odds: f32,
+    /// Log-odds added to every cell a beam passes through, negative.
+    pub miss_log_odds: f32,
+    pub min_log_odds: f32,
+    pub max_log_odds: f32,
+}
+
+impl Default for OccupancyConfig {
+    fn default() -> Self {
+        Self {
+            prior: 0.5,
+            hit_log_odds: 0.85,
+            miss_log_odds: -0.4,
+            min_log_odds: -4.0,
+            max_log_odds: 4.0,
+        }
+    }
+}
+
+/// Single range sensor beam, `range >= max_range` means the beam hit nothing.
+#[derive(Debug, Clone, Copy)]
+pub struct RangeMeasurement {
+    pub origin: Vec2,
+    pub angle: f32,
+    pub range: f32,
+    pub max_range: f32,
+}
+
+impl RangeMeasurement {
+    pub fn is_hit(&self) -> bool { self.range < self.max_range }
+    pub fn end_point(&self) -> Vec2 { self.origin + Vec2::from_angle(self.angle) * self.range.min(self.max_range) }
+}
+
+/// Per cell occupancy probability stored as log-odds, aligned with the `GridMap` cells.
+#[derive(Debug, Clone)]
+pub struct OccupancyGrid {
+    width: usize,
+    height: usize,
+    cell_size: f32,
+    config: OccupancyConfig,
+    log_odds: Vec<f32>,
+}
+
+impl OccupancyGrid {
+    pub fn new(width: usize, height: usize, cell_size: f32, config: OccupancyConfig) -> Self {
+        let prior = log_odds(config.prior);
+        Self {
+            width,
+            height,
+            cell_size,
+            config,
+            log_odds: vec![prior; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn cell_size(&self) -> f32 { self.cell_size }
+    pub fn config(&self) -> &OccupancyConfig { &self.config }
+
+    pub fn log_odds(&self, x: usize, y: usize) -> Option<f32> {
+        let pos = self.get_cell_pos(x, y)?;
+        Some(self.log_odds[pos])
+    }
+
+    pub fn probability(&self, x: usize, y: usize) -> Option<f32> { self.log_odds(x, y).map(probability) }
+
+    pub fn set_probability(&mut self, x: usize, y: usize, prob: f32) -> Option<()> {
+        let pos = self.get_cell_pos(x, y)?;
+        self.log_odds[pos] = log_odds(prob).clamp(self.config.min_log_odds, self.config.max_log_odds);
+        Some(())
+    }
+
+    /// Bayesian update of a single cell with one hit or miss observation.
+    pub fn update_cell(&mut self, x: usize, y: usize, hit: bool) -> Option<()> {
+        let pos = self.get_cell_pos(x, y)?;
+        let delta = if hit {
+            self.config.hit_log_odds
+        } else {
+            self.config.miss_log_odds
+        };
+        self.log_odds[pos] = (self.log_odds[pos] + delta).clamp(self.config.min_log_odds, self.config.max_log_odds);
+        Some(())
+    }
+
+    /// Marks every cell the beam crossed as free and the cell it ended in as occupied.
+    pub fn integrate(&mut self, measurement: &RangeMeasurement) {
+        let end = measurement.end_point();
+        // Hits land exactly on the obstacle's boundary, nudge them into the obstacle cell
+        let end_cell = if measurement.is_hit() {
+            self.world_to_cell(end + Vec2::from_angle(measurement.angle) * self.cell_size * 0.01)
+        } else {
+            self.world_to_cell(end)
+        };
+
+        for (x, y, _) in self.traverse(measurement.origin, end) {
+            if Some((x, y)) == end_cell {
+                continue;
+            }
+            self.update_cell(x, y, false);
+        }
+
+        if let Some((x, y)) = end_cell {
+            self.update_cell(x, y, measurement.is_hit());
+        }
+    }
+
+    pub fn integrate_scan(&mut self, measurements: &[RangeMeasurement]) {
+        for measurement in measurements {
+            self.integrate(measurement);
+        }
+    }
+
+    /// Collision hazard of the segment, `-ln(1 - p)` of every crossed cell weighted by the fraction of it travelled.
+    /// Summed over a path, `1 - exp(-hazard)` is the cumulative collision probability.
+    pub fn segment_hazard(&self, from: Vec2, to: Vec2) -> f64 {
+        self.traverse(from, to)
+            .into_iter()
+            .map(|(x, y, length)| {
+                let prob = self.probability(x, y).unwrap_or(0.0).min(1.0 - f32::EPSILON) as f64;
+                -(1.0 - prob).ln() * (length / self.cell_size) as f64
+            })
+            .sum()
+    }
+
+    pub fn world_to_cell(&self, pos: Vec2) -> Option<(usize, usize)> {
+        if pos.x < 0.0 || pos.y < 0.0 {
+            return None;
+        }
+
+        let x = (pos.x / self.cell_size).floor() as usize;
+        let y = (pos.y / self.cell_size).floor() as usize;
+        self.get_cell_pos(x, y).map(|_| (x, y))
+    }
+
+    /// Cells crossed by the segment together with the length travelled inside each of them.
+    fn traverse(&self, from: Vec2, to: Vec2) -> Vec<(usize, usize, f32)> {
+        let mut cells = Vec::new();
+        let length = from.distance(to);
+        if length == 0.0 {
+            if let Some((x, y)) = self.world_to_cell(from) {
+                cells.push((x, y, 0.0));
+            }
+            return cells;
+        }
+
+        let dir = (to - from) / length;
+        let cell_size = self.cell_size;
+        let mut cell_x = (from.x / cell_size).floor() as isize;
+        let mut cell_y = (from.y / cell_size).floor() as isize;
+
+        let step_x = dir.x.signum() as isize;
+        let step_y = dir.y.signum() as isize;
+        let t_delta_x = if dir.x != 0.0 { cell_size / dir.x.abs() } else { f32::MAX };
+        let t_delta_y = if dir.y != 0.0 { cell_size / dir.y.abs() } else { f32::MAX };
+
+        let mut t_max_x = if dir.x > 0.0 {
+            ((cell_x + 1) as f32 * cell_size - from.x) / dir.x
+        } else if dir.x < 0.0 {
+            (cell_x as f32 * cell_size - from.x) / dir.x
+        } else {
+            f32::MAX
+        };
+        let mut t_max_y = if dir.y > 0.0 {
+            ((cell_y + 1) as f32 * cell_size - from.y) / dir.y
+        } else if dir.y < 0.0 {
+            (cell_y as f32 * cell_size - from.y) / dir.y
+        } else {
+            f32::MAX
+        };
+
+        let mut t = 0.0;
+        while t < length {
+            let t_next = t_max_x.min(t_max_y).min(length);
+            if cell_x >= 0 && cell_y >= 0 && (cell_x as usize) < self.width && (cell_y as usize) < self.height {
+                cells.push((cell_x as usize, cell_y as usize, t_next - t));
+            }
+            t = t_next;
+
+            if t_max_x < t_max_y {
+                t_max_x += t_delta_x;
+                cell_x += step_x;
+            } else {
+                t_max_y += t_delta_y;
+                cell_y += step_y;
+            }
+        }
+
+        cells
+    }
+
+    fn get_cell_pos(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(y * self.width + x)
+    }
+}
+
+pub fn log_odds(prob: f32) -> f32 {
+    let prob = prob.clamp(f32::EPSILON, 1.0 - f32::EPSILON);
+    (prob / (1.0 - prob)).ln()
+}
+
+pub fn probability(log_odds: f32) -> f32 { 1.0 - 1.0 / (1.0 + log_odds.exp()) }
+
+/// Simulates a range sensor at `origin` by casting `beam_count` evenly spaced beams into `world`.
+pub fn simulate_scan(world: &dyn Geometry, origin: Vec2, beam_count: usize, max_range: f32) -> Vec<RangeMeasurement> {
+    (0..beam_count)
+        .map(|i| {
+            let angle = i as f32 / beam_count as f32 * std::f32::consts::TAU;
+            let ray = Ray {
+                root: origin,
+                dir: Vec2::from_angle(angle),
+            };
+
+            let range = world.raycast(ray).map_or(max_range, |hit| hit.dist.min(max_range));
+            RangeMeasurement {
+                origin,
+                angle,
+                range,
+                max_range,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,471 @@
+use crate::world::geometry::{point_segment_distance, segment_intersection, segment_segment_distance, Geometry};
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::types::{quad, Quad, Ray, RayHitInfo};
+use glam::Vec2;
+
+/// Simple (non self-intersecting) polygon, vertices in either winding order.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    vertices: Vec<Vec2>,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vec2>) -> Self {
+        let min = vertices.iter().fold(Vec2::splat(f32::MAX), |acc, v| acc.min(*v));
+        let max = vertices.iter().fold(Vec2::splat(f32::MIN), |acc, v| acc.max(*v));
+        Self { vertices, min, max }
+    }
+
+    pub fn rect(area: Quad) -> Self {
+        Self::new(vec![
+            area.pos,
+            Vec2::new(area.pos.x + area.siz.x, area.pos.y),
+            area.pos + area.siz,
+            Vec2::new(area.pos.x, area.pos.y + area.siz.y),
+        ])
+    }
+
+    pub fn vertices(&self) -> &[Vec2] { &self.vertices }
+
+    pub fn aabb(&self) -> Quad { quad(self.min.x, self.min.y, self.max.x - self.min.x, self.max.y - self.min.y) }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let count = self.vertices.len();
+        (0..count).map(move |i| (self.vertices[i], self.vertices[(i + 1) % count]))
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        if self.vertices.len() < 3 || pt.cmplt(self.min).any() || pt.cmpgt(self.max).any() {
+            return false;
+        }
+
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a.y > pt.y) != (b.y > pt.y) {
+                let cross_x = a.x + (pt.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if pt.x < cross_x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Nearest boundary hit along a normalized direction, as `(distance, normal)`.
+    pub fn raycast(&self, root: Vec2, dir: Vec2) -> Option<(f32, Vec2)> {
+        if self.contains(root) {
+            return Some((0.0, -dir));
+        }
+
+        let mut best: Option<(f32, Vec2)> = None;
+        for (a, b) in self.edges() {
+            let edge = b - a;
+            let denom = dir.perp_dot(edge);
+            if denom == 0.0 {
+                continue;
+            }
+
+            let diff = a - root;
+            let t = diff.perp_dot(edge) / denom;
+            let u = diff.perp_dot(dir) / denom;
+            if t < 0.0 || !(0.0..=1.0).contains(&u) {
+                continue;
+            }
+
+            if best.is_none_or(|(best_t, _)| t < best_t) {
+                let mut normal = edge.perp().normalize_or_zero();
+                if normal.dot(dir) > 0.0 {
+                    normal = -normal;
+                }
+                best = Some((t, normal));
+            }
+        }
+        best
+    }
+
+    /// Parameter intervals of the segment `from -> to` that lie inside the polygon.
+    pub fn clip_segment(&self, from: Vec2, to: Vec2) -> Vec<(f32, f32)> {
+        let mut ts = vec![0.0, 1.0];
+        for (a, b) in self.edges() {
+            if let Some((t, _)) = segment_intersection(from, to, a, b) {
+                ts.push(t);
+            }
+        }
+        ts.sort_by(f32::total_cmp);
+        ts.dedup();
+
+        let mut intervals: Vec<(f32, f32)> = Vec::new();
+        for pair in ts.windows(2) {
+            let (t0, t1) = (pair[0], pair[1]);
+            let mid = from.lerp(to, (t0 + t1) * 0.5);
+            if !self.contains(mid) || self.on_boundary(mid) {
+                continue;
+            }
+
+            match intervals.last_mut() {
+                Some(last) if last.1 == t0 => last.1 = t1,
+                _ => intervals.push((t0, t1)),
+            }
+        }
+        intervals
+    }
+
+    /// `true` if `pt` lies on an edge, `contains` answers such points inconsistently between edges.
+    fn on_boundary(&self, pt: Vec2) -> bool {
+        let tolerance = 1e-4 * (self.max - self.min).max_element().max(1.0);
+        self.edges().any(|(a, b)| point_segment_distance(pt, a, b) <= tolerance)
+    }
+
+    /// `true` if the segment passes through the polygon's interior.
+    pub fn overlaps_segment(&self, from: Vec2, to: Vec2) -> bool {
+        if from == to {
+            return self.contains(from);
+        }
+        !self.clip_segment(from, to).is_empty()
+    }
+
+    /// Distance between the segment and the polygon, zero if they touch or overlap.
+    pub fn segment_distance(&self, from: Vec2, to: Vec2) -> f32 {
+        if self.contains(from) || self.contains(to) {
+            return 0.0;
+        }
+
+        self.edges()
+            .map(|(a, b)| {
+                if from == to {
+                    point_segment_distance(from, a, b)
+                } else {
+                    segment_segment_distance(from, to, a, b)
+                }
+            })
+            .fold(f32::MAX, f32::min)
+    }
+}
+
+/// Uniform bucket grid over the world bounds storing obstacle ids.
+#[derive(Debug, Clone)]
+struct SpatialIndex {
+    origin: Vec2,
+    bucket_size: f32,
+    cols: usize,
+    rows: usize,
+    buckets: Vec<Vec<usize>>,
+}
+
+impl SpatialIndex {
+    fn new(bounds: Quad, bucket_size: f32) -> Self {
+        let cols = ((bounds.siz.x / bucket_size).ceil() as usize).max(1);
+        let rows = ((bounds.siz.y / bucket_size).ceil() as usize).max(1);
+        Self {
+            origin: bounds.pos,
+            bucket_size,
+            cols,
+            rows,
+            buckets: vec![Vec::new(); cols * rows],
+        }
+    }
+
+    fn bucket_coord(&self, pt: Vec2) -> (usize, usize) {
+        let rel = (pt - self.origin) / self.bucket_size;
+        (
+            (rel.x.floor().max(0.0) as usize).min(self.cols - 1),
+            (rel.y.floor().max(0.0) as usize).min(self.rows - 1),
+        )
+    }
+
+    fn insert(&mut self, id: usize, area: Quad) {
+        let (x0, y0) = self.bucket_coord(area.pos);
+        let (x1, y1) = self.bucket_coord(area.pos + area.siz);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.buckets[y * self.cols + x].push(id);
+            }
+        }
+    }
+
+    fn bucket(&self, x: usize, y: usize) -> &[usize] { &self.buckets[y * self.cols + x] }
+
+    fn query(&self, min: Vec2, max: Vec2) -> Vec<usize> {
+        let (x0, y0) = self.bucket_coord(min);
+        let (x1, y1) = self.bucket_coord(max);
+
+        let mut ids = Vec::new();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                ids.extend_from_slice(self.bucket(x, y));
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+/// Continuous world whose obstacles are stored as exact polygons.
+#[derive(Debug, Clone)]
+pub struct PolygonWorld {
+    bounds: Quad,
+    obstacles: Vec<Polygon>,
+    index: SpatialIndex,
+}
+
+impl PolygonWorld {
+    pub fn new(bounds: Quad, bucket_size: f32) -> Self {
+        Self {
+            bounds,
+            obstacles: Vec::new(),
+            index: SpatialIndex::new(bounds, bucket_size),
+        }
+    }
+
+    /// Converts obstacle cells to polygons, merging horizontal runs into single rectangles.
+    pub fn from_grid_map(grid_map: &GridMap) -> Self {
+        let cell_size = grid_map.cell_size();
+        let mut world = Self::new(grid_map.bounds(), cell_size * 4.0);
+
+        for y in 0..grid_map.height() {
+            let mut x = 0;
+            while x < grid_map.width() {
+                if grid_map.grid().get(x, y) != Some(GridNodeValue::Obstacle) {
+                    x += 1;
+                    continue;
+                }
+
+                let run_start = x;
+                while x < grid_map.width() && grid_map.grid().get(x, y) == Some(GridNodeValue::Obstacle) {
+                    x += 1;
+                }
+
+                world.add_obstacle(Polygon::rect(quad(
+                    run_start as f32 * cell_size,
+                    y as f32 * cell_size,
+                    (x - run_start) as f32 * cell_size,
+                    cell_size,
+                )));
+            }
+        }
+
+        world
+    }
+
+    pub fn add_obstacle(&mut self, polygon: Polygon) -> usize {
+        let id = self.obstacles.len();
+        self.index.insert(id, polygon.aabb());
+        self.obstacles.push(polygon);
+        id
+    }
+
+    pub fn obstacles(&self) -> &[Polygon] { &self.obstacles }
+}
+
+impl Geometry for PolygonWorld {
+    fn bounds(&self) -> Quad { self.bounds }
+
+    fn raycast(&self, ray: Ray) -> Option<RayHitInfo> {
+        let dir = ray.dir.normalize_or_zero();
+        if dir == Vec2::ZERO {
+            return None;
+        }
+
+        let bound_min = self.bounds.pos;
+        let bound_max = self.bounds.pos + self.bounds.siz;
+
+        let mut t_enter: f32 = 0.0;
+        let mut t_exit = f32::MAX;
+        for axis in 0..2 {
+            if dir[axis] == 0.0 {
+                if ray.root[axis] < bound_min[axis] || ray.root[axis] > bound_max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let t1 = (bound_min[axis] - ray.root[axis]) / dir[axis];
+            let t2 = (bound_max[axis] - ray.root[axis]) / dir[axis];
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+        }
+        if t_enter > t_exit {
+            return None;
+        }
+
+        let index = &self.index;
+        let bucket_size = index.bucket_size;
+        let (start_x, start_y) = index.bucket_coord(ray.root + dir * t_enter);
+        let mut bucket_x = start_x as isize;
+        let mut bucket_y = start_y as isize;
+
+        let step_x = dir.x.signum() as isize;
+        let step_y = dir.y.signum() as isize;
+        let t_delta_x = if dir.x != 0.0 { bucket_size / dir.x.abs() } else { f32::MAX };
+        let t_delta_y = if dir.y != 0.0 { bucket_size / dir.y.abs() } else { f32::MAX };
+
+        let mut t_max_x = if dir.x > 0.0 {
+            (index.origin.x + (bucket_x + 1) as f32 * bucket_size - ray.root.x) / dir.x
+        } else if dir.x < 0.0 {
+            (index.origin.x + bucket_x as f32 * bucket_size - ray.root.x) / dir.x
+        } else {
+            f32::MAX
+        };
+        let mut t_max_y = if dir.y > 0.0 {
+            (index.origin.y + (bucket_y + 1) as f32 * bucket_size - ray.root.y) / dir.y
+        } else if dir.y < 0.0 {
+            (index.origin.y + bucket_y as f32 * bucket_size - ray.root.y) / dir.y
+        } else {
+            f32::MAX
+        };
+
+        let mut best: Option<(f32, Vec2)> = None;
+        while bucket_x >= 0 && bucket_x < index.cols as isize && bucket_y >= 0 && bucket_y < index.rows as isize {
+            for &id in index.bucket(bucket_x as usize, bucket_y as usize) {
+                if let Some((t, normal)) = self.obstacles[id].raycast(ray.root, dir)
+                    && best.is_none_or(|(best_t, _)| t < best_t)
+                {
+                    best = Some((t, normal));
+                }
+            }
+
+            // Hits inside the current bucket can't be beaten by obstacles further along the ray
+            if best.is_some_and(|(t, _)| t <= t_max_x.min(t_max_y)) {
+                break;
+            }
+
+            if t_max_x < t_max_y {
+                t_max_x += t_delta_x;
+                bucket_x += step_x;
+            } else {
+                t_max_y += t_delta_y;
+                bucket_y += step_y;
+            }
+        }
+
+        best.map(|(dist, nor)| RayHitInfo {
+            pt: ray.root + dir * dist,
+            nor,
+            dist,
+        })
+    }
+
+    fn is_point_in_obstacle(&self, pt: Vec2) -> bool {
+        let (x, y) = self.index.bucket_coord(pt);
+        self.index.bucket(x, y).iter().any(|&id| self.obstacles[id].contains(pt))
+    }
+
+    fn is_segment_clear(&self, from: Vec2, to: Vec2, radius: f32) -> bool {
+        let radius = radius.max(0.0);
+        let min = from.min(to) - Vec2::splat(radius);
+        let max = from.max(to) + Vec2::splat(radius);
+
+        self.index.query(min, max).into_iter().all(|id| {
+            let obstacle = &self.obstacles[id];
+            if radius > 0.0 {
+                obstacle.segment_distance(from, to) >= radius
+            } else {
+                !obstacle.overlaps_segment(from, to)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+
+    /// A 100 x 100 grid of 10 unit cells with a wall, a lone block and cells touching the world's edge, together
+    /// with the polygon world converted from it.
+    fn worlds() -> (GridMap, PolygonWorld) {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (10, 10),
+            cell_size: 10.0,
+        });
+        for x in 3..6 {
+            grid_map.grid_mut().set(x, 4, GridNodeValue::Obstacle);
+        }
+        grid_map.grid_mut().set(6, 6, GridNodeValue::Obstacle);
+        grid_map.grid_mut().set(0, 9, GridNodeValue::Obstacle);
+        grid_map.grid_mut().set(9, 0, GridNodeValue::Obstacle);
+        let polygon_world = PolygonWorld::from_grid_map(&grid_map);
+        (grid_map, polygon_world)
+    }
+
+    #[test]
+    fn raycasts_match_the_grid() {
+        let (grid_map, polygon_world) = worlds();
+        let rays = [
+            (Vec2::new(45.0, 5.0), Vec2::Y),
+            (Vec2::new(45.0, 95.0), -Vec2::Y),
+            (Vec2::new(5.0, 45.0), Vec2::X),
+            (Vec2::new(5.0, 5.0), Vec2::new(1.0, 1.0)),
+            (Vec2::new(95.0, 55.0), Vec2::new(-1.0, 0.2)),
+            (Vec2::new(5.0, 55.0), Vec2::new(1.0, 0.1)),
+            (Vec2::new(55.0, 95.0), Vec2::X),
+            (Vec2::new(35.0, 45.0), Vec2::X),
+            (Vec2::new(95.0, 95.0), Vec2::new(1.0, 1.0)),
+        ];
+        for (root, dir) in rays {
+            let ray = Ray { root, dir };
+            let grid_hit = Geometry::raycast(&grid_map, ray).map(|hit| hit.dist);
+            let polygon_hit = polygon_world.raycast(ray).map(|hit| hit.dist);
+            match (grid_hit, polygon_hit) {
+                (Some(grid), Some(polygon)) => assert!((grid - polygon).abs() < 1e-3, "{} {}: {} {}", root, dir, grid, polygon),
+                (grid, polygon) => assert_eq!(grid, polygon, "{} {}", root, dir),
+            }
+        }
+    }
+
+    #[test]
+    fn segment_checks_match_the_grid() {
+        let (grid_map, polygon_world) = worlds();
+        let segments = [
+            // Through the wall, the block and an edge cell
+            (Vec2::new(45.0, 5.0), Vec2::new(45.0, 95.0), 0.0),
+            (Vec2::new(55.0, 65.0), Vec2::new(75.0, 65.0), 0.0),
+            (Vec2::new(5.0, 85.0), Vec2::new(5.0, 99.0), 0.0),
+            // Grazing the wall's lower edge and the block's corner
+            (Vec2::new(5.0, 40.0), Vec2::new(95.0, 40.0), 0.0),
+            (Vec2::new(25.0, 50.0), Vec2::new(75.0, 50.0), 0.0),
+            (Vec2::new(25.0, 95.0), Vec2::new(95.0, 25.0), 0.0),
+            // Along the seam between two of the wall's cells
+            (Vec2::new(40.0, 35.0), Vec2::new(40.0, 55.0), 0.0),
+            (Vec2::new(50.0, 45.0), Vec2::new(50.0, 25.0), 0.0),
+            // Along the world's boundary, past the cells touching it
+            (Vec2::new(0.0, 0.0), Vec2::new(0.0, 100.0), 0.0),
+            (Vec2::new(0.0, 100.0), Vec2::new(100.0, 100.0), 0.0),
+            (Vec2::new(100.0, 100.0), Vec2::new(100.0, 0.0), 0.0),
+            (Vec2::new(10.0, 100.0), Vec2::new(10.0, 90.0), 0.0),
+            // Widened by a radius
+            (Vec2::new(5.0, 34.0), Vec2::new(95.0, 34.0), 5.0),
+            (Vec2::new(5.0, 30.0), Vec2::new(95.0, 30.0), 5.0),
+            (Vec2::new(5.0, 5.0), Vec2::new(5.0, 95.0), 5.0),
+        ];
+        for (from, to, radius) in segments {
+            assert_eq!(
+                Geometry::is_segment_clear(&grid_map, from, to, radius),
+                polygon_world.is_segment_clear(from, to, radius),
+                "{} -> {} at radius {}",
+                from,
+                to,
+                radius
+            );
+        }
+    }
+
+    #[test]
+    fn bounds_match_the_grid() {
+        let (grid_map, polygon_world) = worlds();
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(50.0, 100.0),
+            Vec2::new(-0.1, 50.0),
+            Vec2::new(50.0, 100.1),
+            Vec2::new(45.0, 45.0),
+        ];
+        for pt in points {
+            assert_eq!(Geometry::contains(&grid_map, pt), polygon_world.contains(pt), "{}", pt);
+        }
+    }
+}
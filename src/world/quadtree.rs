@@ -0,0 +1,305 @@
+use crate::world::grid::{Grid, GridNodeValue};
+use glam::Vec2;
+
+#[derive(Debug, Clone)]
+enum QuadNode {
+    Leaf(GridNodeValue),
+    Branch(Box<[QuadNode; 4]>),
+}
+
+/// Uniform square block of cells, may reach past the grid edge where cells count as obstacles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuadLeaf {
+    pub x: usize,
+    pub y: usize,
+    pub size: usize,
+    pub value: GridNodeValue,
+}
+
+impl QuadLeaf {
+    pub fn center(&self, cell_size: f32) -> Vec2 {
+        Vec2::new(
+            (self.x as f32 + self.size as f32 * 0.5) * cell_size,
+            (self.y as f32 + self.size as f32 * 0.5) * cell_size,
+        )
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.size && y < self.y + self.size
+    }
+}
+
+/// Shared boundary between two adjacent free leaves, endpoints in cell corner coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Portal {
+    pub to: usize,
+    pub from_corner: (usize, usize),
+    pub to_corner: (usize, usize),
+}
+
+impl Portal {
+    pub fn endpoints(&self, cell_size: f32) -> (Vec2, Vec2) {
+        (
+            Vec2::new(self.from_corner.0 as f32, self.from_corner.1 as f32) * cell_size,
+            Vec2::new(self.to_corner.0 as f32, self.to_corner.1 as f32) * cell_size,
+        )
+    }
+
+    pub fn midpoint(&self, cell_size: f32) -> Vec2 {
+        let (a, b) = self.endpoints(cell_size);
+        (a + b) * 0.5
+    }
+}
+
+/// Free leaves of a quadtree and the portals connecting them.
+#[derive(Debug, Clone)]
+pub struct LeafGraph {
+    pub leaves: Vec<QuadLeaf>,
+    pub portals: Vec<Vec<Portal>>,
+}
+
+impl LeafGraph {
+    pub fn leaf_at(&self, x: usize, y: usize) -> Option<usize> { self.leaves.iter().position(|leaf| leaf.contains(x, y)) }
+}
+
+/// Region quadtree over a `Grid`, uniform free or blocked areas collapse into single leaves.
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    root: QuadNode,
+    size: usize,
+    width: usize,
+    height: usize,
+}
+
+impl QuadTree {
+    pub fn new(grid: &Grid) -> Self {
+        let size = grid.width().max(grid.height()).max(1).next_power_of_two();
+        Self {
+            root: Self::build(grid, 0, 0, size),
+            size,
+            width: grid.width(),
+            height: grid.height(),
+        }
+    }
+
+    fn build(grid: &Grid, x: usize, y: usize, size: usize) -> QuadNode {
+        if size == 1 {
+            return QuadNode::Leaf(grid.get(x, y).unwrap_or(GridNodeValue::Obstacle));
+        }
+
+        let half = size / 2;
+        let children = [
+            Self::build(grid, x, y, half),
+            Self::build(grid, x + half, y, half),
+            Self::build(grid, x, y + half, half),
+            Self::build(grid, x + half, y + half, half),
+        ];
+        Self::merge(children)
+    }
+
+    fn merge(children: [QuadNode; 4]) -> QuadNode {
+        if let QuadNode::Leaf(first) = children[0]
+            && children.iter().all(|child| matches!(child, QuadNode::Leaf(value) if *value == first))
+        {
+            return QuadNode::Leaf(first);
+        }
+        QuadNode::Branch(Box::new(children))
+    }
+
+    /// Applies a single cell change, splitting the leaf it falls into and merging back up where possible.
+    pub fn update(&mut self, x: usize, y: usize, value: GridNodeValue) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        Self::update_node(&mut self.root, 0, 0, self.size, x, y, value);
+    }
+
+    fn update_node(node: &mut QuadNode, nx: usize, ny: usize, size: usize, x: usize, y: usize, value: GridNodeValue) {
+        if let QuadNode::Leaf(current) = node {
+            if *current == value {
+                return;
+            }
+            if size == 1 {
+                *current = value;
+                return;
+            }
+
+            let current = *current;
+            *node = QuadNode::Branch(Box::new(std::array::from_fn(|_| QuadNode::Leaf(current))));
+        }
+
+        let QuadNode::Branch(children) = node else {
+            return;
+        };
+
+        let half = size / 2;
+        let quadrant = (x >= nx + half) as usize + 2 * (y >= ny + half) as usize;
+        let child_x = nx + half * (quadrant % 2);
+        let child_y = ny + half * (quadrant / 2);
+        Self::update_node(&mut children[quadrant], child_x, child_y, half, x, y, value);
+
+        let merged = Self::merge(std::mem::replace(
+            children.as_mut(),
+            std::array::from_fn(|_| QuadNode::Leaf(GridNodeValue::Obstacle)),
+        ));
+        *node = merged;
+    }
+
+    pub fn leaves(&self) -> Vec<QuadLeaf> {
+        let mut leaves = Vec::new();
+        Self::collect_leaves(&self.root, 0, 0, self.size, &mut leaves);
+        leaves
+    }
+
+    fn collect_leaves(node: &QuadNode, x: usize, y: usize, size: usize, leaves: &mut Vec<QuadLeaf>) {
+        match node {
+            QuadNode::Leaf(value) => leaves.push(QuadLeaf {
+                x,
+                y,
+                size,
+                value: *value,
+            }),
+            QuadNode::Branch(children) => {
+                let half = size / 2;
+                for (i, child) in children.iter().enumerate() {
+                    Self::collect_leaves(child, x + half * (i % 2), y + half * (i / 2), half, leaves);
+                }
+            }
+        }
+    }
+
+    pub fn leaf_count(&self) -> usize { self.leaves().len() }
+
+    pub fn leaf_at(&self, x: usize, y: usize) -> Option<QuadLeaf> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let (mut node, mut nx, mut ny, mut size) = (&self.root, 0, 0, self.size);
+        loop {
+            match node {
+                QuadNode::Leaf(value) => {
+                    return Some(QuadLeaf {
+                        x: nx,
+                        y: ny,
+                        size,
+                        value: *value,
+                    });
+                }
+                QuadNode::Branch(children) => {
+                    size /= 2;
+                    let quadrant = (x >= nx + size) as usize + 2 * (y >= ny + size) as usize;
+                    nx += size * (quadrant % 2);
+                    ny += size * (quadrant / 2);
+                    node = &children[quadrant];
+                }
+            }
+        }
+    }
+
+    /// Adjacency graph of the free leaves, leaves sharing an edge segment are linked by a portal.
+    pub fn free_leaf_graph(&self) -> LeafGraph {
+        let leaves: Vec<QuadLeaf> = self
+            .leaves()
+            .into_iter()
+            .filter(|leaf| leaf.value == GridNodeValue::Air)
+            .collect();
+
+        let mut cell_leaf = vec![usize::MAX; self.width * self.height];
+        for (id, leaf) in leaves.iter().enumerate() {
+            for y in leaf.y..(leaf.y + leaf.size).min(self.height) {
+                for x in leaf.x..(leaf.x + leaf.size).min(self.width) {
+                    cell_leaf[y * self.width + x] = id;
+                }
+            }
+        }
+
+        let mut portals: Vec<Vec<Portal>> = vec![Vec::new(); leaves.len()];
+        for (id, leaf) in leaves.iter().enumerate() {
+            // Only look right and down, the opposite direction is added at the same time
+            let right = leaf.x + leaf.size;
+            if right < self.width {
+                self.link_side(&cell_leaf, &mut portals, id, right, leaf.y..leaf.y + leaf.size, true);
+            }
+            let bottom = leaf.y + leaf.size;
+            if bottom < self.height {
+                self.link_side(&cell_leaf, &mut portals, id, bottom, leaf.x..leaf.x + leaf.size, false);
+            }
+        }
+
+        LeafGraph { leaves, portals }
+    }
+
+    /// Links `id` to the free leaves just across the grid line `line`, one portal per run of cells
+    /// belonging to the same neighbour. `vertical` lines run along y at x = `line`.
+    fn link_side(
+        &self,
+        cell_leaf: &[usize],
+        portals: &mut [Vec<Portal>],
+        id: usize,
+        line: usize,
+        range: std::ops::Range<usize>,
+        vertical: bool,
+    ) {
+        let limit = if vertical { self.height } else { self.width };
+        let corner = |along: usize| if vertical { (line, along) } else { (along, line) };
+
+        let mut along = range.start;
+        let end = range.end.min(limit);
+        while along < end {
+            let (x, y) = corner(along);
+            let other = cell_leaf[y * self.width + x];
+
+            let run_start = along;
+            while along < end && {
+                let (x, y) = corner(along);
+                cell_leaf[y * self.width + x] == other
+            } {
+                along += 1;
+            }
+
+            if other != usize::MAX {
+                let portal = Portal {
+                    to: other,
+                    from_corner: corner(run_start),
+                    to_corner: corner(along),
+                };
+                portals[other].push(Portal { to: id, ..portal });
+                portals[id].push(portal);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::seeded_rng;
+
+    #[test]
+    fn updates_match_a_rebuild() {
+        for seed in 0..5 {
+            let rng = seeded_rng(seed);
+            let mut grid = Grid::new(13, 9);
+            let mut tree = QuadTree::new(&grid);
+
+            // Scattered obstacles split the tree, clearing them all again has to merge it back into the same leaves
+            let mut cells: Vec<(usize, usize)> = (0..200)
+                .map(|_| (rng.gen_range(0, grid.width()), rng.gen_range(0, grid.height())))
+                .collect();
+            for (step, &(x, y)) in cells.iter().enumerate() {
+                let value = if step % 3 == 2 { GridNodeValue::Air } else { GridNodeValue::Obstacle };
+                grid.set(x, y, value);
+                tree.update(x, y, value);
+                assert_eq!(tree.leaves(), QuadTree::new(&grid).leaves(), "seed {} step {}", seed, step);
+            }
+
+            cells.reverse();
+            for &(x, y) in &cells {
+                grid.set(x, y, GridNodeValue::Air);
+                tree.update(x, y, GridNodeValue::Air);
+                assert_eq!(tree.leaves(), QuadTree::new(&grid).leaves(), "seed {} clearing ({}, {})", seed, x, y);
+            }
+        }
+    }
+}
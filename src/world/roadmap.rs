@@ -0,0 +1,121 @@
+use glam::Vec2;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Weighted graph of free space positions. Edge costs must be at least the euclidean length of the edge,
+/// queries rely on it to keep the straight line heuristic admissible.
+#[derive(Debug, Clone, Default)]
+pub struct Roadmap {
+    nodes: Vec<Vec2>,
+    edges: Vec<Vec<(usize, f32)>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SearchNode {
+    node: usize,
+    f: f32,
+}
+
+impl Ord for SearchNode {
+    fn cmp(&self, other: &Self) -> Ordering { other.f.total_cmp(&self.f) }
+}
+impl PartialOrd for SearchNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl PartialEq for SearchNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for SearchNode {}
+
+impl Roadmap {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn add_node(&mut self, pos: Vec2) -> usize {
+        self.nodes.push(pos);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize, cost: f32) {
+        self.edges[a].push((b, cost));
+        self.edges[b].push((a, cost));
+    }
+
+    pub fn nodes(&self) -> &[Vec2] { &self.nodes }
+    pub fn edges(&self, node: usize) -> &[(usize, f32)] { self.edges.get(node).map_or(&[], |edges| edges) }
+    pub fn node_count(&self) -> usize { self.nodes.len() }
+    pub fn edge_count(&self) -> usize { self.edges.iter().map(Vec::len).sum::<usize>() / 2 }
+
+    /// Cheapest path from `start` to `goal` through the roadmap, as `(path, nodes expanded)`. The query points are
+    /// not part of the roadmap, `start_links` and `goal_links` connect them to nodes and `direct` is the cost of going
+    /// straight if allowed.
+    pub fn shortest_path(
+        &self,
+        start: Vec2,
+        goal: Vec2,
+        start_links: &[(usize, f32)],
+        goal_links: &[(usize, f32)],
+        direct: Option<f32>,
+    ) -> Option<(Vec<Vec2>, usize)> {
+        let count = self.nodes.len();
+        let mut goal_cost = vec![f32::INFINITY; count];
+        for &(node, cost) in goal_links {
+            goal_cost[node] = goal_cost[node].min(cost);
+        }
+
+        let mut g_costs = vec![f32::INFINITY; count];
+        let mut predecessors = vec![usize::MAX; count];
+        let mut queue = BinaryHeap::new();
+        for &(node, cost) in start_links {
+            if cost < g_costs[node] {
+                g_costs[node] = cost;
+                queue.push(SearchNode {
+                    node,
+                    f: cost + self.nodes[node].distance(goal),
+                });
+            }
+        }
+
+        // Best complete path so far, `usize::MAX` stands for the direct connection
+        let mut best = direct.map(|cost| (cost, usize::MAX));
+        let mut expanded = 0;
+        while let Some(cur) = queue.pop() {
+            if best.is_some_and(|(cost, _)| cur.f >= cost) {
+                break;
+            }
+
+            let cur_g = g_costs[cur.node];
+            if cur.f > cur_g + self.nodes[cur.node].distance(goal) {
+                continue;
+            }
+            expanded += 1;
+
+            let finish = cur_g + goal_cost[cur.node];
+            if finish.is_finite() && best.is_none_or(|(cost, _)| finish < cost) {
+                best = Some((finish, cur.node));
+            }
+
+            for &(next, cost) in self.edges[cur.node].iter() {
+                let new_g = cur_g + cost;
+                if new_g < g_costs[next] {
+                    g_costs[next] = new_g;
+                    predecessors[next] = cur.node;
+                    queue.push(SearchNode {
+                        node: next,
+                        f: new_g + self.nodes[next].distance(goal),
+                    });
+                }
+            }
+        }
+
+        let (_, mut node) = best?;
+        let mut path = vec![goal];
+        while node != usize::MAX {
+            path.push(self.nodes[node]);
+            node = predecessors[node];
+        }
+        path.push(start);
+        path.reverse();
+        Some((path, expanded))
+    }
+}
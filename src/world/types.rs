@@ -1,4 +1,4 @@
-use macroquad::prelude::*;
+use glam::Vec2;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Quad {
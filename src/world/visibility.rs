@@ -0,0 +1,155 @@
+use crate::world::geometry::Geometry;
+use crate::world::grid::{GridMap, GridNodeValue};
+use crate::world::roadmap::Roadmap;
+use glam::Vec2;
+
+/// Visibility graph over the convex obstacle corners of a `GridMap`, gives exact any-angle shortest paths.
+#[derive(Debug, Clone)]
+pub struct VisibilityGraph {
+    roadmap: Roadmap,
+}
+
+impl VisibilityGraph {
+    /// Fraction of a cell corner vertices are pushed into free space, rays rooted exactly on an
+    /// obstacle corner would start inside the obstacle cell.
+    const CORNER_OFFSET: f32 = 1e-3;
+
+    pub fn new(grid_map: &GridMap) -> Self {
+        let mut roadmap = Roadmap::new();
+        for corner in convex_corners(grid_map) {
+            roadmap.add_node(corner);
+        }
+
+        let nodes = roadmap.nodes().to_vec();
+        for a in 0..nodes.len() {
+            for b in a + 1..nodes.len() {
+                if is_visible(grid_map, nodes[a], nodes[b]) {
+                    roadmap.add_edge(a, b, nodes[a].distance(nodes[b]));
+                }
+            }
+        }
+
+        Self { roadmap }
+    }
+
+    pub fn roadmap(&self) -> &Roadmap { &self.roadmap }
+    pub fn vertices(&self) -> &[Vec2] { self.roadmap.nodes() }
+
+    /// Shortest path between two points of `world`, which must be the map the graph was built from, as
+    /// `(path, nodes expanded)`. Start and goal are linked to every vertex they see for this query only.
+    pub fn find_path(&self, world: &dyn Geometry, start: Vec2, goal: Vec2) -> Option<(Vec<Vec2>, usize)> {
+        let links = |from: Vec2| -> Vec<(usize, f32)> {
+            self.vertices()
+                .iter()
+                .enumerate()
+                .filter(|(_, vertex)| is_visible(world, from, **vertex))
+                .map(|(id, vertex)| (id, from.distance(*vertex)))
+                .collect()
+        };
+
+        let direct = is_visible(world, start, goal).then(|| start.distance(goal));
+        self.roadmap
+            .shortest_path(start, goal, &links(start), &links(goal), direct)
+    }
+}
+
+impl GridMap {
+    pub fn build_visibility_graph(&self) -> VisibilityGraph { VisibilityGraph::new(self) }
+}
+
+fn is_visible(world: &dyn Geometry, a: Vec2, b: Vec2) -> bool { world.has_line_of_sight(a, b) && world.has_line_of_sight(b, a) }
+
+/// Grid corners where free space turns around an obstacle, nudged into every free cell that touches them.
+/// Cells outside the map count as obstacles, so the map border never produces vertices.
+fn convex_corners(grid_map: &GridMap) -> Vec<Vec2> {
+    let grid = grid_map.grid();
+    let cell_size = grid_map.cell_size();
+    let blocked = |x: isize, y: isize| {
+        x < 0 || y < 0 || grid.get(x as usize, y as usize).is_none_or(|value| value == GridNodeValue::Obstacle)
+    };
+
+    let mut corners = Vec::new();
+    for cy in 0..=grid_map.height() as isize {
+        for cx in 0..=grid_map.width() as isize {
+            // Cells around the corner, top-left, top-right, bottom-left, bottom-right
+            let around = [
+                (-1, -1, blocked(cx - 1, cy - 1)),
+                (0, -1, blocked(cx, cy - 1)),
+                (-1, 0, blocked(cx - 1, cy)),
+                (0, 0, blocked(cx, cy)),
+            ];
+            let obstacles = around.iter().filter(|(_, _, is_blocked)| *is_blocked).count();
+            let diagonal = around[0].2 == around[3].2 && around[1].2 == around[2].2;
+            if obstacles != 1 && !(obstacles == 2 && diagonal) {
+                continue;
+            }
+
+            let corner = Vec2::new(cx as f32, cy as f32) * cell_size;
+            for &(dx, dy, is_blocked) in around.iter() {
+                if obstacles == 1 && is_blocked {
+                    // Push away from the single obstacle into the opposite free cell
+                    let dir = Vec2::new(if dx < 0 { 1.0 } else { -1.0 }, if dy < 0 { 1.0 } else { -1.0 });
+                    corners.push(corner + dir * cell_size * VisibilityGraph::CORNER_OFFSET);
+                } else if obstacles == 2 && !is_blocked {
+                    let dir = Vec2::new(if dx < 0 { -1.0 } else { 1.0 }, if dy < 0 { -1.0 } else { 1.0 });
+                    corners.push(corner + dir * cell_size * VisibilityGraph::CORNER_OFFSET);
+                }
+            }
+        }
+    }
+    corners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+
+    /// A 100 x 100 world with a single 20 x 20 block in the middle.
+    fn block_map() -> GridMap {
+        let mut grid_map = GridMap::new(WorldConfig {
+            grid_size: (10, 10),
+            cell_size: 10.0,
+        });
+        for y in 4..6 {
+            for x in 4..6 {
+                grid_map.grid_mut().set(x, y, GridNodeValue::Obstacle);
+            }
+        }
+        grid_map
+    }
+
+    #[test]
+    fn links_the_corners_that_see_each_other() {
+        let graph = VisibilityGraph::new(&block_map());
+        let vertices = graph.vertices();
+        assert_eq!(vertices.len(), 4);
+
+        // Corners along a side see each other, the block hides the opposite corner
+        for (a, &from) in vertices.iter().enumerate() {
+            let linked: Vec<usize> = graph.roadmap().edges(a).iter().map(|&(b, _)| b).collect();
+            assert_eq!(linked.len(), 2);
+            for b in linked {
+                let to = vertices[b];
+                assert!((from.x - to.x).abs() < 1.0 || (from.y - to.y).abs() < 1.0);
+            }
+        }
+        assert_eq!(graph.roadmap().edge_count(), 4);
+    }
+
+    #[test]
+    fn finds_the_shortest_path_around_the_block() {
+        let grid_map = block_map();
+        let graph = VisibilityGraph::new(&grid_map);
+        let (start, goal) = (Vec2::new(20.0, 50.0), Vec2::new(80.0, 50.0));
+        let (path, _) = graph.find_path(&grid_map, start, goal).unwrap();
+
+        // Up to one side of the block, along it and back down
+        assert_eq!(path.len(), 4);
+        assert!(path[1].distance(Vec2::new(40.0, 40.0)) < 0.1 || path[1].distance(Vec2::new(40.0, 60.0)) < 0.1);
+        assert!((path[1].y - path[2].y).abs() < 1e-3);
+        let length: f32 = path.windows(2).map(|seg| seg[0].distance(seg[1])).sum();
+        let optimal = 2.0 * Vec2::new(20.0, 10.0).length() + 20.0;
+        assert!((length - optimal).abs() < 0.1, "{} vs {}", length, optimal);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::world::polygon::Polygon;
+use crate::world::types::Quad;
+use glam::Vec2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoneKind {
+    /// Hard exclusion, paths must never enter.
+    NoGo,
+    /// Cheaper to travel through, `cost_factor` is expected to be below 1.
+    Preferred { cost_factor: f32 },
+    /// Travel is slowed down to `speed_factor` of normal speed, so each unit costs `1 / speed_factor`.
+    SpeedLimited { speed_factor: f32 },
+}
+
+impl ZoneKind {
+    /// Cost multiplier per unit of length, `None` for hard exclusions.
+    pub fn cost_factor(&self) -> Option<f32> {
+        match self {
+            ZoneKind::NoGo => None,
+            ZoneKind::Preferred { cost_factor } => Some(cost_factor.max(0.0)),
+            ZoneKind::SpeedLimited { speed_factor } => Some(1.0 / speed_factor.max(f32::EPSILON)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub name: String,
+    pub area: Polygon,
+    pub kind: ZoneKind,
+}
+
+impl Zone {
+    pub fn polygon(name: &str, area: Polygon, kind: ZoneKind) -> Self {
+        Self {
+            name: name.to_string(),
+            area,
+            kind,
+        }
+    }
+
+    pub fn rect(name: &str, area: Quad, kind: ZoneKind) -> Self { Self::polygon(name, Polygon::rect(area), kind) }
+
+    /// Length of the segment `from -> to` lying inside the zone.
+    pub fn clipped_length(&self, from: Vec2, to: Vec2) -> f32 {
+        let length = from.distance(to);
+        self.area.clip_segment(from, to).iter().map(|(t0, t1)| (t1 - t0) * length).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneCrossing {
+    pub name: String,
+    pub kind: ZoneKind,
+    /// Path length travelled inside the zone.
+    pub length: f32,
+}
+
+/// Named regions carrying meaning on top of the obstacle grid.
+#[derive(Debug, Clone, Default)]
+pub struct ZoneLayer {
+    zones: Vec<Zone>,
+}
+
+impl ZoneLayer {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn add(&mut self, zone: Zone) { self.zones.push(zone); }
+
+    pub fn remove(&mut self, name: &str) -> Option<Zone> {
+        let index = self.zones.iter().position(|zone| zone.name == name)?;
+        Some(self.zones.remove(index))
+    }
+
+    pub fn clear(&mut self) { self.zones.clear(); }
+
+    pub fn zones(&self) -> &[Zone] { &self.zones }
+    pub fn is_empty(&self) -> bool { self.zones.is_empty() }
+
+    pub fn is_point_allowed(&self, pt: Vec2) -> bool {
+        self.zones
+            .iter()
+            .all(|zone| zone.kind != ZoneKind::NoGo || !zone.area.contains(pt))
+    }
+
+    pub fn is_segment_allowed(&self, from: Vec2, to: Vec2) -> bool {
+        self.zones
+            .iter()
+            .all(|zone| zone.kind != ZoneKind::NoGo || !zone.area.overlaps_segment(from, to))
+    }
+
+    /// Smallest cost multiplier any zone applies, used to keep distance heuristics admissible.
+    pub fn min_cost_factor(&self) -> f32 {
+        self.zones
+            .iter()
+            .filter_map(|zone| zone.kind.cost_factor())
+            .fold(1.0, f32::min)
+    }
+
+    /// Segment length weighted by the soft zones it crosses. Overlapping zones stack multiplicatively.
+    pub fn segment_cost(&self, from: Vec2, to: Vec2) -> f32 {
+        let length = from.distance(to);
+        if length == 0.0 || self.zones.is_empty() {
+            return length;
+        }
+
+        let soft_zones: Vec<(&Zone, f32)> = self
+            .zones
+            .iter()
+            .filter_map(|zone| zone.kind.cost_factor().map(|factor| (zone, factor)))
+            .collect();
+
+        // Split the segment on every zone boundary and price each piece by the zones covering it
+        let mut ts = vec![0.0, 1.0];
+        for (zone, _) in soft_zones.iter() {
+            for (t0, t1) in zone.area.clip_segment(from, to) {
+                ts.push(t0);
+                ts.push(t1);
+            }
+        }
+        ts.sort_by(f32::total_cmp);
+        ts.dedup();
+
+        ts.windows(2)
+            .map(|pair| {
+                let mid = from.lerp(to, (pair[0] + pair[1]) * 0.5);
+                let factor: f32 = soft_zones
+                    .iter()
+                    .filter(|(zone, _)| zone.area.contains(mid))
+                    .map(|(_, factor)| factor)
+                    .product();
+                (pair[1] - pair[0]) * length * factor
+            })
+            .sum()
+    }
+
+    pub fn path_cost(&self, path: &[Vec2]) -> f32 { path.windows(2).map(|seg| self.segment_cost(seg[0], seg[1])).sum() }
+
+    /// Zones entered by the path, in the order of first entry.
+    pub fn crossed_zones(&self, path: &[Vec2]) -> Vec<ZoneCrossing> {
+        let mut crossings: Vec<(f32, ZoneCrossing)> = Vec::new();
+
+        for zone in self.zones.iter() {
+            let mut travelled = 0.0;
+            let mut first_entry: Option<f32> = None;
+            let mut length = 0.0;
+
+            for seg in path.windows(2) {
+                let segment_length = seg[0].distance(seg[1]);
+                for (t0, t1) in zone.area.clip_segment(seg[0], seg[1]) {
+                    first_entry.get_or_insert(travelled + t0 * segment_length);
+                    length += (t1 - t0) * segment_length;
+                }
+                travelled += segment_length;
+            }
+
+            if path.len() == 1 && zone.area.contains(path[0]) {
+                first_entry = Some(0.0);
+            }
+
+            if let Some(entry) = first_entry {
+                crossings.push((
+                    entry,
+                    ZoneCrossing {
+                        name: zone.name.clone(),
+                        kind: zone.kind,
+                        length,
+                    },
+                ));
+            }
+        }
+
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        crossings.into_iter().map(|(_, crossing)| crossing).collect()
+    }
+}